@@ -0,0 +1,112 @@
+//! `--annotate DATE:LABEL` markers: a small flag above a day's bar, with
+//! labels stacked upward where they would otherwise overlap.
+
+use crate::date::Date;
+use crate::escape::escape_text;
+use crate::github::{Day, Week};
+use crate::options::Options;
+use crate::render::{cell_height, cell_position, stroke_width};
+use svg::node::element::{Group, Path, Polygon, Text as SvgText};
+use svg::node::Text as TextNode;
+
+const FLAG_COLOR: &str = "#d73a49";
+const POLE_HEIGHT: f64 = 26.0;
+const LEVEL_HEIGHT: f64 = 18.0;
+// Rough advance per character at the label's font size, for overlap checks
+const CHAR_WIDTH: f64 = 7.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Annotation {
+    pub date: Date,
+    pub label: String,
+}
+
+impl std::str::FromStr for Annotation {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (date, label) = s.split_once(':').ok_or_else(|| format!("expected DATE:LABEL, got {:?}", s))?;
+        let date = date.parse()?;
+        let label = label.trim();
+        if label.is_empty() {
+            return Err(format!("empty label for {}", date));
+        }
+        Ok(Annotation { date, label: label.to_string() })
+    }
+}
+
+// Week column, day index and the day itself for a date in the calendar.
+fn locate(weeks: &[Week], date: Date) -> Option<(usize, usize, &Day)> {
+    weeks.iter().enumerate().find_map(|(x, w)| w.contribution_days.iter().enumerate().find(|(_, d)| d.date == Some(date)).map(|(i, d)| (x, i, d)))
+}
+
+/// Annotations whose date isn't in the calendar and so can't be drawn.
+pub fn unplaced_annotations<'a>(weeks: &[Week], opts: &'a Options) -> Vec<&'a Annotation> {
+    opts.annotations.iter().filter(|a| locate(weeks, a.date).is_none()).collect()
+}
+
+pub(crate) fn draw_annotations(weeks: &[Week], opts: &Options) -> Group {
+    let proj = &opts.projection;
+    let mut anchors: Vec<((f64, f64), &str)> = opts
+        .annotations
+        .iter()
+        .filter_map(|a| {
+            let (x, index, day) = locate(weeks, a.date)?;
+            let (col, row) = cell_position(x, index, day, opts.week_start);
+            Some((proj.project(col as f64 + 0.5, row as f64 + 0.5, cell_height(day.contribution_count, opts)), a.label.as_str()))
+        })
+        .collect();
+    anchors.sort_by(|a, b| a.0.0.total_cmp(&b.0.0));
+
+    // Greedy stacking: each label takes the lowest level whose previous label
+    // ends before it starts.
+    let mut level_ends: Vec<f64> = Vec::new();
+    let mut g = Group::new();
+    for ((x, y), label) in anchors {
+        let level = level_ends.iter().position(|&end| end < x).unwrap_or(level_ends.len());
+        let end = x + 8.0 + label.chars().count() as f64 * CHAR_WIDTH;
+        match level_ends.get_mut(level) {
+            Some(slot) => *slot = end,
+            None => level_ends.push(end),
+        }
+        let top = y - POLE_HEIGHT - level as f64 * LEVEL_HEIGHT;
+        g = g.add(Path::new().set("d", format!("M {} {} L {} {}", x, y, x, top)).set("stroke", FLAG_COLOR).set("stroke-width", stroke_width(1.5, opts)));
+        g = g.add(Polygon::new().set("points", format!("{},{} {},{} {},{}", x, top, x + 8.0, top + 4.0, x, top + 8.0)).set("fill", FLAG_COLOR));
+        g = g.add(SvgText::new().set("x", x + 10.0).set("y", top + 8.0).set("fill", FLAG_COLOR).set("font-size", 12).add(TextNode::new(escape_text(label))));
+    }
+    g
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::demo::demo_user;
+
+    #[test]
+    fn annotation_parses_date_and_label() {
+        let a: Annotation = "2025-09-17:v1.0 release".parse().unwrap();
+        assert_eq!((a.date.to_string().as_str(), a.label.as_str()), ("2025-09-17", "v1.0 release"));
+        assert!("2025-13-01:nope".parse::<Annotation>().is_err());
+        assert!("first commit".parse::<Annotation>().is_err());
+    }
+
+    #[test]
+    fn overlapping_labels_stack_and_missing_dates_are_reported() {
+        let user = demo_user();
+        let weeks = &user.contributions_collection.contribution_calendar.weeks;
+        let opts = Options {
+            annotations: ["2025-09-17:spike", "2025-09-18:next day", "1999-01-01:too early"].iter().map(|s| s.parse().unwrap()).collect(),
+            ..Options::default()
+        };
+        let missing: Vec<String> = unplaced_annotations(weeks, &opts).iter().map(|a| a.label.clone()).collect();
+        assert_eq!(missing, vec!["too early"]);
+
+        let svg = draw_annotations(weeks, &opts).to_string();
+        assert_eq!(svg.matches("<text").count(), 2);
+        let ys: Vec<f64> = svg.match_indices("<text").map(|(i, _)| {
+            let rest = &svg[i..];
+            let start = rest.find(" y=\"").unwrap() + 4;
+            rest[start..start + rest[start..].find('"').unwrap()].parse().unwrap()
+        }).collect();
+        assert!(ys[0] != ys[1], "adjacent labels should sit on different levels: {:?}", ys);
+    }
+}
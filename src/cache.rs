@@ -0,0 +1,137 @@
+//! Content-addressed, bounded LRU cache of rendered SVGs.
+//!
+//! Rendering is pure in `(user, options)`, so a service that renders the same
+//! profile repeatedly can key on a hash of both and skip the work entirely.
+
+use crate::github::User;
+use crate::options::Options;
+use crate::render::render_profile;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// Hashes the render inputs. Options are hashed through their `Debug` form so
+/// newly added fields are covered without touching this function.
+pub fn cache_key(user: &User, opts: &Options) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(user).unwrap_or_default().hash(&mut hasher);
+    // An svg Element keeps its attributes in a HashMap, so its Debug form
+    // varies between equal instances; Display sorts them
+    format!("{:?}", Options { extra_defs: Vec::new(), ..opts.clone() }).hash(&mut hasher);
+    for def in &opts.extra_defs {
+        def.to_string().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+pub struct RenderCache {
+    capacity: usize,
+    entries: HashMap<u64, Arc<String>>,
+    // Least recently used key at the front
+    order: VecDeque<u64>,
+}
+
+impl RenderCache {
+    pub fn new(capacity: usize) -> Self {
+        RenderCache { capacity: capacity.max(1), entries: HashMap::new(), order: VecDeque::new() }
+    }
+
+    /// Returns the serialized SVG for these inputs, rendering only on a miss.
+    pub fn render(&mut self, user: &User, opts: &Options) -> Arc<String> {
+        let key = cache_key(user, opts);
+        if let Some(svg) = self.entries.get(&key).cloned() {
+            self.touch(key);
+            return svg;
+        }
+
+        let svg = Arc::new(render_profile(user, opts).to_string());
+        if self.entries.len() >= self.capacity && let Some(oldest) = self.order.pop_front() {
+            self.entries.remove(&oldest);
+        }
+        self.entries.insert(key, svg.clone());
+        self.order.push_back(key);
+        svg
+    }
+
+    pub fn contains(&self, user: &User, opts: &Options) -> bool {
+        self.entries.contains_key(&cache_key(user, opts))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear_cache(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    fn touch(&mut self, key: u64) {
+        self.order.retain(|k| *k != key);
+        self.order.push_back(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use svg::node::element::Element;
+    use svg::node::Node;
+
+    fn user(total: i32) -> User {
+        serde_json::from_value(serde_json::json!({
+            "contributionsCollection": {
+                "totalCommitContributions": total, "totalIssueContributions": 0,
+                "totalPullRequestContributions": 0, "totalPullRequestReviewContributions": 0,
+                "totalRepositoryContributions": 0,
+                "contributionCalendar": { "totalContributions": total, "weeks": [{ "contributionDays": [{ "contributionCount": total }] }] }
+            },
+            "repositories": { "nodes": [] }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn identical_inputs_hit_the_cache() {
+        let mut cache = RenderCache::new(4);
+        let opts = Options::default();
+        let first = cache.render(&user(3), &opts);
+        assert!(cache.contains(&user(3), &opts));
+        let second = cache.render(&user(3), &opts);
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(cache.len(), 1);
+
+        cache.clear_cache();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn equal_defs_give_equal_keys() {
+        let with_filter = || {
+            let mut filter = Element::new("filter");
+            for attr in ["id", "x", "y", "width", "height", "filterUnits", "color-interpolation-filters"] {
+                filter.assign(attr, "1");
+            }
+            Options::default().with_def(filter)
+        };
+        assert_eq!(cache_key(&user(3), &with_filter()), cache_key(&user(3), &with_filter()));
+        assert_ne!(cache_key(&user(3), &with_filter()), cache_key(&user(3), &Options::default()));
+    }
+
+    #[test]
+    fn least_recently_used_entry_is_evicted() {
+        let mut cache = RenderCache::new(2);
+        let opts = Options::default();
+        cache.render(&user(1), &opts);
+        cache.render(&user(2), &opts);
+        cache.render(&user(1), &opts);
+        cache.render(&user(3), &opts);
+        assert!(cache.contains(&user(1), &opts));
+        assert!(!cache.contains(&user(2), &opts));
+    }
+}
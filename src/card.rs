@@ -0,0 +1,106 @@
+//! Plain card inputs for callers that have their own data, such as a service
+//! rendering cards on demand, so nothing has to be shaped like a GitHub
+//! GraphQL response.
+
+use crate::github::{ContributionCalendar, ContributionsCollection, DailyCounts, LangEdge, LangNode, Languages, RepoNode, Repositories, User};
+use crate::merge::weeks_from_days;
+use crate::options::Options;
+use crate::render::render_profile;
+use svg::Document;
+
+/// Everything a card shows. Missing days between the first and last entry
+/// of `days` are drawn as empty.
+#[derive(Clone, Debug, Default)]
+pub struct CardData {
+    pub login: String,
+    /// Display name for `--show-name`; the login is used without one.
+    pub name: Option<String>,
+    pub days: DailyCounts,
+    pub totals: ActivityTotals,
+    pub repos: Vec<CardRepo>,
+}
+
+/// The radar's five axes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ActivityTotals {
+    pub commits: i32,
+    pub issues: i32,
+    pub pull_requests: i32,
+    pub reviews: i32,
+    pub repositories: i32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CardRepo {
+    pub name: String,
+    pub stars: i32,
+    pub forks: i32,
+    pub languages: Vec<CardLanguage>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CardLanguage {
+    pub name: String,
+    /// Hex color; the donut picks one for languages without it.
+    pub color: Option<String>,
+    pub bytes: i32,
+}
+
+impl CardData {
+    /// The same data as a `User`, the form the renderer works on.
+    pub fn to_user(&self) -> User {
+        let nodes = self
+            .repos
+            .iter()
+            .map(|repo| RepoNode {
+                name: repo.name.clone(),
+                stargazer_count: repo.stars,
+                fork_count: repo.forks,
+                languages: Some(Languages {
+                    edges: repo.languages.iter().map(|l| LangEdge { size: l.bytes, node: LangNode { name: l.name.clone(), color: l.color.clone() } }).collect(),
+                }),
+            })
+            .collect();
+        User {
+            login: self.login.clone(),
+            name: self.name.clone(),
+            avatar_url: None,
+            created_at: None,
+            contributions_collection: ContributionsCollection {
+                total_commit_contributions: self.totals.commits,
+                total_issue_contributions: self.totals.issues,
+                total_pull_request_contributions: self.totals.pull_requests,
+                total_pull_request_review_contributions: self.totals.reviews,
+                total_repository_contributions: self.totals.repositories,
+                contribution_calendar: ContributionCalendar { total_contributions: self.days.values().sum(), weeks: weeks_from_days(&self.days) },
+            },
+            repositories: Repositories { nodes, page_info: None },
+        }
+    }
+}
+
+/// Renders the full profile card from plain data; see `render_profile`.
+pub fn render_card(data: &CardData, opts: &Options) -> Document {
+    render_profile(&data.to_user(), opts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::Date;
+
+    #[test]
+    fn card_data_renders_without_a_github_response() {
+        let data = CardData {
+            login: "octocat".to_string(),
+            days: [(Date::parse("2024-05-01").unwrap(), 3), (Date::parse("2024-05-09").unwrap(), 5)].into_iter().collect(),
+            totals: ActivityTotals { commits: 8, ..ActivityTotals::default() },
+            repos: vec![CardRepo { name: "hello".to_string(), languages: vec![CardLanguage { name: "Rust".to_string(), color: Some("#dea584".to_string()), bytes: 100 }], ..CardRepo::default() }],
+            ..CardData::default()
+        };
+        let user = data.to_user();
+        assert_eq!(user.contributions_collection.contribution_calendar.total_contributions, 8);
+        assert_eq!(user.contributions_collection.contribution_calendar.weeks.len(), 2);
+        assert!(render_card(&data, &Options::default()).to_string().contains("Rust"));
+    }
+}
@@ -0,0 +1,246 @@
+//! Minimal proleptic Gregorian calendar dates, enough for the contribution
+//! calendar (ISO `YYYY-MM-DD` strings from the API) and range arithmetic.
+//!
+//! This stands in for `chrono` or `time`, which the crate doesn't depend on:
+//! the card only needs whole days, weekdays, ISO weeks and fixed UTC offsets,
+//! which fit in a few functions over days since the epoch. Anything beyond
+//! that, such as named time zones, belongs in one of those crates instead.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+pub fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+impl Date {
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Date> {
+        (day >= 1 && day <= days_in_month(year, month)).then_some(Date { year, month, day })
+    }
+
+    /// Parses `YYYY-MM-DD`, ignoring any trailing time component so API
+    /// timestamps like `2015-03-04T12:00:00Z` are accepted too.
+    pub fn parse(s: &str) -> Option<Date> {
+        let date = s.get(..10)?;
+        let mut parts = date.split('-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        if s.len() > 10 && !s[10..].starts_with('T') {
+            return None;
+        }
+        Date::new(year, month, day)
+    }
+
+    /// Today's date in UTC.
+    pub fn today() -> Date {
+        let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        Date::from_days((secs / 86_400) as i64)
+    }
+
+    /// Days since 1970-01-01 (Howard Hinnant's `days_from_civil`).
+    pub fn days_since_epoch(&self) -> i64 {
+        let y = self.year as i64 - if self.month <= 2 { 1 } else { 0 };
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let m = self.month as i64;
+        let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    pub fn from_days(days: i64) -> Date {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+        Date { year, month, day }
+    }
+
+    pub fn add_days(&self, days: i64) -> Date {
+        Date::from_days(self.days_since_epoch() + days)
+    }
+
+    /// ISO 8601 week-numbering year and week (1..=53). Weeks start on Monday
+    /// and belong to the year holding their Thursday, so late-December days
+    /// can fall in week 1 of the next year and early-January days in the last
+    /// week of the previous one.
+    pub fn iso_week(&self) -> (i32, u32) {
+        let iso_weekday = (self.weekday() + 6) % 7; // 0 = Monday
+        let thursday = self.add_days(3 - iso_weekday as i64);
+        let jan1 = Date { year: thursday.year, month: 1, day: 1 };
+        let ordinal = thursday.days_since_epoch() - jan1.days_since_epoch();
+        (thursday.year, (ordinal / 7 + 1) as u32)
+    }
+
+    /// Day of the week, 0 = Sunday (matching GitHub's `weekday`).
+    pub fn weekday(&self) -> u32 {
+        // 1970-01-01 was a Thursday
+        (self.days_since_epoch() + 4).rem_euclid(7) as u32
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl std::str::FromStr for Date {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Date::parse(s).ok_or_else(|| format!("invalid date: {} (expected YYYY-MM-DD)", s))
+    }
+}
+
+impl Serialize for Date {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A fixed offset from UTC for `--timezone`. GitHub buckets the contribution
+/// calendar into days itself; the offset decides the day boundaries of the
+/// ranges this tool requests, what "today" is, and which day a timestamped
+/// event (e.g. for `--color-by`) lands on. Without a timezone database, named
+/// zones and daylight saving are not modelled: pass the offset in effect.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UtcOffset {
+    minutes: i32,
+}
+
+impl UtcOffset {
+    pub const UTC: UtcOffset = UtcOffset { minutes: 0 };
+
+    pub fn from_minutes(minutes: i32) -> Option<UtcOffset> {
+        (minutes.abs() <= 14 * 60).then_some(UtcOffset { minutes })
+    }
+
+    pub fn today(&self) -> Date {
+        let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64);
+        Date::from_days((secs + self.minutes as i64 * 60).div_euclid(86_400))
+    }
+
+    /// The local date of a UTC timestamp such as `2024-05-01T23:30:00Z`.
+    pub fn date_of(&self, timestamp: &str) -> Option<Date> {
+        let date = Date::parse(timestamp)?;
+        let hour: i32 = timestamp.get(11..13).map_or(Some(0), |h| h.parse().ok())?;
+        let minute: i32 = timestamp.get(14..16).map_or(Some(0), |m| m.parse().ok())?;
+        let local = hour * 60 + minute + self.minutes;
+        Some(date.add_days(local.div_euclid(24 * 60) as i64))
+    }
+}
+
+impl fmt::Display for UtcOffset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.minutes == 0 {
+            return write!(f, "Z");
+        }
+        let sign = if self.minutes < 0 { '-' } else { '+' };
+        write!(f, "{}{:02}:{:02}", sign, self.minutes.abs() / 60, self.minutes.abs() % 60)
+    }
+}
+
+impl std::str::FromStr for UtcOffset {
+    type Err = String;
+    /// `UTC`, `Z`, or `+HH:MM` / `-HH:MM`. Named zones are rejected rather
+    /// than guessed at, since their offset depends on the date.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || format!("invalid timezone: {} (expected UTC or a fixed offset ±HH:MM like +05:30 or -08:00; named zones aren't supported)", s);
+        if s.eq_ignore_ascii_case("utc") || s == "Z" {
+            return Ok(UtcOffset::UTC);
+        }
+        let (sign, rest) = match s.as_bytes().first() {
+            Some(b'+') => (1, &s[1..]),
+            Some(b'-') => (-1, &s[1..]),
+            _ => return Err(err()),
+        };
+        let Some((hh, mm)) = rest.split_once(':').filter(|(h, m)| h.len() == 2 && m.len() == 2) else { return Err(err()) };
+        if !hh.bytes().chain(mm.bytes()).all(|b| b.is_ascii_digit()) {
+            return Err(err());
+        }
+        let (hours, minutes) = (hh.parse::<i32>().map_err(|_| err())?, mm.parse::<i32>().map_err(|_| err())?);
+        if minutes >= 60 {
+            return Err(err());
+        }
+        UtcOffset::from_minutes(sign * (hours * 60 + minutes)).ok_or_else(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> Date {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn iso_weeks_across_year_boundaries() {
+        assert_eq!(date("2025-01-01").iso_week(), (2025, 1));
+        // Monday 2024-12-30 starts week 1 of 2025
+        assert_eq!(date("2024-12-29").iso_week(), (2024, 52));
+        assert_eq!(date("2024-12-30").iso_week(), (2025, 1));
+        // 2021-01-03 is the Sunday ending 2020's week 53
+        assert_eq!(date("2021-01-03").iso_week(), (2020, 53));
+        assert_eq!(date("2021-01-04").iso_week(), (2021, 1));
+        assert_eq!(date("2025-12-31").iso_week(), (2026, 1));
+    }
+
+    #[test]
+    fn leap_days_exist_only_in_leap_years() {
+        assert!(Date::new(2024, 2, 29).is_some() && Date::new(2000, 2, 29).is_some());
+        assert!(Date::new(2023, 2, 29).is_none() && Date::new(1900, 2, 29).is_none());
+        assert!(Date::parse("2023-02-29").is_none());
+        assert_eq!(date("2024-02-28").add_days(1), date("2024-02-29"));
+        assert_eq!(date("2024-02-29").add_days(1), date("2024-03-01"));
+        assert_eq!(date("2023-02-28").add_days(1), date("2023-03-01"));
+        assert_eq!(date("2025-01-01").days_since_epoch() - date("2024-01-01").days_since_epoch(), 366);
+        // Thursday of ISO week 9
+        assert_eq!((date("2024-02-29").weekday(), date("2024-02-29").iso_week()), (4, (2024, 9)));
+        assert_eq!(Date::from_days(date("2024-02-29").days_since_epoch()), date("2024-02-29"));
+    }
+
+    #[test]
+    fn offsets_shift_timestamps_across_midnight() {
+        let ist: UtcOffset = "+05:30".parse().unwrap();
+        let pst: UtcOffset = "-08:00".parse().unwrap();
+        assert_eq!(ist.date_of("2024-05-01T20:00:00Z"), Some(date("2024-05-02")));
+        assert_eq!(pst.date_of("2024-05-01T05:00:00Z"), Some(date("2024-04-30")));
+        assert_eq!(UtcOffset::UTC.date_of("2024-05-01T23:59:00Z"), Some(date("2024-05-01")));
+        assert_eq!((ist.to_string(), pst.to_string(), UtcOffset::UTC.to_string()), ("+05:30".into(), "-08:00".into(), "Z".into()));
+        for bad in ["+15:00", "IST", "Europe/Berlin", "+05:75", "-08", "+0530"] {
+            assert!(bad.parse::<UtcOffset>().unwrap_err().contains("±HH:MM"), "{}", bad);
+        }
+    }
+}
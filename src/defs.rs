@@ -0,0 +1,125 @@
+//! The document's `<defs>` block: caller-supplied definitions plus built-ins
+//! such as the `--shadow` drop-shadow filter and the `--bg-gradient` fill.
+
+use crate::escape::escape_attr;
+use crate::options::{Background, Options};
+use svg::node::element::{Circle, ClipPath, Definitions, Element, Filter, LinearGradient, Stop};
+use svg::node::Node;
+
+pub const SHADOW_FILTER_ID: &str = "hm-shadow";
+pub const BACKGROUND_GRADIENT_ID: &str = "hm-bg";
+pub const AVATAR_CLIP_ID: &str = "hm-avatar";
+
+fn primitive(name: &str, attrs: &[(&str, &str)]) -> Element {
+    let mut el = Element::new(name);
+    for (k, v) in attrs {
+        el.assign(*k, *v);
+    }
+    el
+}
+
+fn merge_node(input: Option<&str>) -> Element {
+    match input {
+        Some(input) => primitive("feMergeNode", &[("in", input)]),
+        None => primitive("feMergeNode", &[]),
+    }
+}
+
+/// Soft drop shadow: blurred, offset alpha composited under the source.
+pub fn shadow_filter() -> Filter {
+    let mut merge = Element::new("feMerge");
+    merge.append(merge_node(None));
+    merge.append(merge_node(Some("SourceGraphic")));
+    let mut transfer = Element::new("feComponentTransfer");
+    transfer.append(primitive("feFuncA", &[("type", "linear"), ("slope", "0.35")]));
+
+    Filter::new()
+        .set("id", SHADOW_FILTER_ID)
+        .set("x", "-10%").set("y", "-10%").set("width", "120%").set("height", "130%")
+        .add(primitive("feGaussianBlur", &[("in", "SourceAlpha"), ("stdDeviation", "4")]))
+        .add(primitive("feOffset", &[("dx", "3"), ("dy", "6"), ("result", "offsetblur")]))
+        .add(transfer)
+        .add(merge)
+}
+
+/// Vertical gradient with evenly spaced stops, for the canvas background.
+pub fn background_gradient(stops: &[String]) -> LinearGradient {
+    let mut gradient = LinearGradient::new().set("id", BACKGROUND_GRADIENT_ID).set("x1", 0).set("y1", 0).set("x2", 0).set("y2", 1);
+    let last = stops.len().saturating_sub(1).max(1) as f64;
+    for (i, color) in stops.iter().enumerate() {
+        gradient = gradient.add(Stop::new().set("offset", i as f64 / last).set("stop-color", color.as_str()));
+    }
+    gradient
+}
+
+/// Circle inscribed in whatever it clips, for the `--show-avatar` image.
+pub fn avatar_clip() -> ClipPath {
+    ClipPath::new().set("id", AVATAR_CLIP_ID).set("clipPathUnits", "objectBoundingBox").add(Circle::new().set("cx", 0.5).set("cy", 0.5).set("r", 0.5))
+}
+
+/// Builds `<defs>`, or `None` when nothing needs defining.
+pub(crate) fn build_defs(opts: &Options) -> Option<Definitions> {
+    let gradient = match &opts.background {
+        Background::Gradient(stops) => Some(background_gradient(stops)),
+        _ => None,
+    };
+    if opts.extra_defs.is_empty() && !opts.shadow && gradient.is_none() && !opts.show_avatar {
+        return None;
+    }
+    let mut defs = Definitions::new();
+    if opts.shadow {
+        defs = defs.add(shadow_filter());
+    }
+    if let Some(gradient) = gradient {
+        defs = defs.add(gradient);
+    }
+    if opts.show_avatar {
+        defs = defs.add(avatar_clip());
+    }
+    for def in &opts.extra_defs {
+        defs = defs.add(def.clone());
+    }
+    Some(defs)
+}
+
+/// The `filter` value for the bar group: an explicit id wins over `--shadow`.
+pub(crate) fn bar_filter(opts: &Options) -> Option<String> {
+    let id = opts.bar_filter.as_deref().or(opts.shadow.then_some(SHADOW_FILTER_ID))?;
+    Some(format!("url(#{})", escape_attr(id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::demo::demo_user;
+    use crate::render::render_profile;
+
+    // The `<defs>` block and the start tag of the group carrying `filter`.
+    fn defs_and_filtered_tag(svg: &str, filter: &str) -> (String, String) {
+        let defs = svg.split("<defs>").nth(1).and_then(|rest| rest.split("</defs>").next()).expect("a <defs> block").to_string();
+        let at = svg.find(&format!(r#"filter="{}""#, filter)).expect("a filtered group");
+        let start = svg[..at].rfind('<').unwrap();
+        (defs, svg[start..at].to_string())
+    }
+
+    #[test]
+    fn extra_defs_and_bar_filter_reach_the_document() {
+        let blur = Filter::new().set("id", "soft").add(primitive("feGaussianBlur", &[("stdDeviation", "2")]));
+        // Interactive bars carry a class, so the filtered group can be told apart
+        let opts = Options { interactive: true, ..Options::default() }.with_def(blur).with_bar_filter("soft");
+        let svg = render_profile(&demo_user(), &opts).to_string();
+        let (defs, tag) = defs_and_filtered_tag(&svg, "url(#soft)");
+        assert!(defs.contains(r#"id="soft""#) && defs.contains("feGaussianBlur"));
+        assert!(tag.starts_with("<g "));
+        assert!(svg[svg.find("url(#soft)").unwrap()..].contains("hm-bar"));
+    }
+
+    #[test]
+    fn shadow_defines_and_applies_its_filter() {
+        let svg = render_profile(&demo_user(), &Options { shadow: true, ..Options::default() }).to_string();
+        let (defs, tag) = defs_and_filtered_tag(&svg, &format!("url(#{})", SHADOW_FILTER_ID));
+        assert!(defs.contains(&format!(r#"id="{}""#, SHADOW_FILTER_ID)));
+        assert!(tag.starts_with("<g "));
+        assert!(!render_profile(&demo_user(), &Options::default()).to_string().contains("<defs>"));
+    }
+}
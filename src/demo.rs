@@ -0,0 +1,9 @@
+//! A bundled sample profile for trying the renderer without a token.
+
+use crate::github::User;
+
+const DEMO_JSON: &str = include_str!("../fixtures/demo.json");
+
+pub fn demo_user() -> User {
+    serde_json::from_str(DEMO_JSON).expect("bundled demo fixture is valid")
+}
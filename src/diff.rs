@@ -0,0 +1,40 @@
+//! Comparing the current series against a saved snapshot to find new activity.
+
+use crate::github::{iter_days, DailyCounts, Day, User};
+
+/// Daily counts from a previous run.
+pub type Baseline = DailyCounts;
+
+pub fn baseline_from_user(user: &User) -> Baseline {
+    iter_days(&user.contributions_collection.contribution_calendar.weeks)
+        .filter_map(|(_, day)| Some((day.date?, day.contribution_count)))
+        .collect()
+}
+
+/// Whether a day gained contributions since the baseline. Dates the baseline
+/// never saw (the range grew) count as entirely new; undated days can't be
+/// matched and are never flagged.
+pub fn is_new_activity(baseline: &Baseline, day: &Day) -> bool {
+    let Some(date) = day.date else { return false };
+    day.contribution_count > baseline.get(&date).copied().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::date::Date;
+
+    fn day(date: &str, count: i32) -> Day {
+        Day { contribution_count: count, date: Date::parse(date) }
+    }
+
+    #[test]
+    fn increased_and_unseen_days_are_new() {
+        let baseline = Baseline::from([(Date::parse("2024-05-01").unwrap(), 3), (Date::parse("2024-05-02").unwrap(), 2)]);
+        assert!(is_new_activity(&baseline, &day("2024-05-01", 4)));
+        assert!(!is_new_activity(&baseline, &day("2024-05-02", 2)));
+        assert!(is_new_activity(&baseline, &day("2024-05-03", 1)));
+        assert!(!is_new_activity(&baseline, &day("2024-05-04", 0)));
+        assert!(!is_new_activity(&baseline, &Day { contribution_count: 9, date: None }));
+    }
+}
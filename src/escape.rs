@@ -0,0 +1,47 @@
+//! Escaping for user-controlled strings written into the SVG.
+//!
+//! The `svg` crate writes text nodes and attribute values verbatim, so a
+//! language name like `R&D` or an annotation containing `<` would otherwise
+//! produce malformed XML. Non-ASCII text (CJK, accents) needs no treatment:
+//! the document is UTF-8 and those characters pass through unchanged.
+
+/// Escapes `s` for use as element text. Quotes are left alone, which keeps
+/// embedded JSON (see `--embed-data`) compact.
+pub fn escape_text(s: &str) -> String {
+    escape(s, false)
+}
+
+/// Escapes `s` for use inside a quoted attribute value.
+pub fn escape_attr(s: &str) -> String {
+    escape(s, true)
+}
+
+fn escape(s: &str, quotes: bool) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' if quotes => out.push_str("&quot;"),
+            '\'' if quotes => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn special_characters_are_escaped_and_unicode_kept() {
+        assert_eq!(escape_text("Tom & <Jerry>"), "Tom &amp; &lt;Jerry&gt;");
+        assert_eq!(escape_attr(r#"say "hi" 'there'"#), "say &quot;hi&quot; &apos;there&apos;");
+        assert_eq!(escape_text(r#"{"a":1}"#), r#"{"a":1}"#);
+        assert_eq!(escape_text("日本語 · café"), "日本語 · café");
+        // Already-escaped input is escaped again rather than passed through
+        assert_eq!(escape_text("&amp;"), "&amp;amp;");
+    }
+}
@@ -0,0 +1,202 @@
+//! Gzip encoding for `.svgz` output.
+//!
+//! A small self-contained DEFLATE encoder: greedy LZ77 matching over a 32 KiB
+//! window, emitted as one block with the fixed Huffman codes (RFC 1951 §3.2.6).
+//! That gives up a little ratio against dynamic codes, but SVG markup is
+//! repetitive enough that back-references do most of the work.
+//!
+//! `flate2::write::GzEncoder` would replace all of this, but flate2 isn't
+//! available to this build (it has to work from the vendored registry
+//! offline). Until it can be added, the output is checked against the
+//! system `gzip` rather than a decoder written alongside the encoder.
+
+const WINDOW: usize = 32 * 1024;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+// Candidates tried per position; more finds longer matches, slower
+const MAX_CHAIN: usize = 64;
+const HASH_BITS: u32 = 15;
+
+const LENGTH_BASE: [u16; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DIST_BASE: [u16; 30] = [1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577];
+const DIST_EXTRA: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+
+// DEFLATE packs bits starting from the least significant; Huffman codes go
+// in most significant bit first.
+struct BitWriter {
+    out: Vec<u8>,
+    acc: u32,
+    len: u32,
+}
+
+impl BitWriter {
+    fn bits(&mut self, value: u32, count: u32) {
+        for i in 0..count {
+            self.acc |= ((value >> i) & 1) << self.len;
+            self.len += 1;
+            if self.len == 8 {
+                self.out.push(self.acc as u8);
+                (self.acc, self.len) = (0, 0);
+            }
+        }
+    }
+
+    fn code(&mut self, code: u32, len: u32) {
+        for i in (0..len).rev() {
+            self.bits(code >> i, 1);
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.len > 0 {
+            self.out.push(self.acc as u8);
+        }
+        self.out
+    }
+}
+
+// Fixed Huffman code for a literal/length symbol (0..=287).
+fn fixed_code(symbol: u32) -> (u32, u32) {
+    match symbol {
+        0..=143 => (0x30 + symbol, 8),
+        144..=255 => (0x190 + symbol - 144, 9),
+        256..=279 => (symbol - 256, 7),
+        _ => (0xc0 + symbol - 280, 8),
+    }
+}
+
+// Index of the last base not above `value`.
+fn bucket(bases: &[u16], value: usize) -> usize {
+    bases.partition_point(|&b| b as usize <= value) - 1
+}
+
+fn write_match(w: &mut BitWriter, len: usize, dist: usize) {
+    let li = bucket(&LENGTH_BASE, len);
+    let (code, bits) = fixed_code(257 + li as u32);
+    w.code(code, bits);
+    w.bits((len - LENGTH_BASE[li] as usize) as u32, LENGTH_EXTRA[li] as u32);
+    let di = bucket(&DIST_BASE, dist);
+    w.code(di as u32, 5);
+    w.bits((dist - DIST_BASE[di] as usize) as u32, DIST_EXTRA[di] as u32);
+}
+
+fn hash(data: &[u8], i: usize) -> usize {
+    let v = (data[i] as u32) << 16 | (data[i + 1] as u32) << 8 | data[i + 2] as u32;
+    (v.wrapping_mul(2_654_435_761) >> (32 - HASH_BITS)) as usize
+}
+
+// Most recent position per hash of the next three bytes, and for each
+// position the previous one with the same hash.
+struct HashChains {
+    head: Vec<usize>,
+    prev: Vec<usize>,
+}
+
+impl HashChains {
+    fn insert(&mut self, data: &[u8], i: usize) {
+        if i + MIN_MATCH <= data.len() {
+            let h = hash(data, i);
+            self.prev[i] = self.head[h];
+            self.head[h] = i;
+        }
+    }
+}
+
+/// Raw DEFLATE stream for `data`.
+pub fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut w = BitWriter { out: Vec::with_capacity(data.len() / 4), acc: 0, len: 0 };
+    w.bits(1, 1); // final block
+    w.bits(1, 2); // fixed Huffman codes
+
+    let mut chains = HashChains { head: vec![usize::MAX; 1 << HASH_BITS], prev: vec![usize::MAX; data.len()] };
+
+    let mut i = 0;
+    while i < data.len() {
+        let (mut best_len, mut best_dist) = (0, 0);
+        if i + MIN_MATCH <= data.len() {
+            let mut candidate = chains.head[hash(data, i)];
+            let limit = (data.len() - i).min(MAX_MATCH);
+            for _ in 0..MAX_CHAIN {
+                if candidate == usize::MAX || i - candidate > WINDOW {
+                    break;
+                }
+                let len = data[candidate..].iter().zip(&data[i..i + limit]).take_while(|(a, b)| a == b).count();
+                if len > best_len {
+                    (best_len, best_dist) = (len, i - candidate);
+                    if len == limit {
+                        break;
+                    }
+                }
+                candidate = chains.prev[candidate];
+            }
+        }
+        if best_len >= MIN_MATCH {
+            write_match(&mut w, best_len, best_dist);
+            for j in i..i + best_len {
+                chains.insert(data, j);
+            }
+            i += best_len;
+        } else {
+            let (code, bits) = fixed_code(data[i] as u32);
+            w.code(code, bits);
+            chains.insert(data, i);
+            i += 1;
+        }
+    }
+    let (code, bits) = fixed_code(256);
+    w.code(code, bits);
+    w.finish()
+}
+
+/// CRC-32 (IEEE) as used by the gzip trailer.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// `data` as a gzip member (RFC 1952) with no name and a zero timestamp, so
+/// the same input always produces the same bytes.
+pub fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff];
+    out.extend(deflate(data));
+    out.extend(crc32(data).to_le_bytes());
+    out.extend((data.len() as u32).to_le_bytes());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    #[test]
+    fn gzipped_card_decompresses_to_the_same_svg() {
+        let svg = crate::render_profile(&crate::demo::demo_user(), &crate::Options::default()).to_string();
+        let gz = gzip(svg.as_bytes());
+        assert_eq!(&gz[..3], &[0x1f, 0x8b, 8]);
+        assert!(gz.len() * 4 < svg.len(), "{} bytes from {}", gz.len(), svg.len());
+        assert_eq!(&gz[gz.len() - 8..gz.len() - 4], &crc32(svg.as_bytes()).to_le_bytes());
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+
+        // An independent decoder, so a bug can't cancel itself out
+        let Ok(mut child) = Command::new("gzip").arg("-dc").stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped()).spawn() else {
+            eprintln!("gzip not found; skipping the round trip");
+            return;
+        };
+        // Written from another thread so a full stdout pipe can't stall gzip
+        let mut stdin = child.stdin.take().unwrap();
+        let writer = std::thread::spawn(move || stdin.write_all(&gz));
+        let output = child.wait_with_output().unwrap();
+        writer.join().unwrap().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), svg);
+    }
+}
@@ -0,0 +1,30 @@
+//! Renders a 3D isometric GitHub contribution card (heatmap, language donut
+//! and activity radar) as an SVG document.
+
+#[cfg(feature = "render-cache")]
+pub mod cache;
+pub mod annotate;
+pub mod card;
+pub mod cli;
+pub mod date;
+pub mod defs;
+pub mod demo;
+pub mod diff;
+pub mod escape;
+pub mod github;
+pub mod gzip;
+pub mod locale;
+pub mod merge;
+pub mod options;
+pub mod output;
+pub mod presets;
+pub mod render;
+pub mod rest;
+mod rings;
+pub mod stats;
+pub mod theme;
+
+pub use card::{render_card, CardData};
+pub use options::{parse_args, Granularity, HeatmapLayout, Options, Style, WeekStart};
+pub use output::to_data_uri;
+pub use render::render_profile;
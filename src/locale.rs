@@ -0,0 +1,149 @@
+//! Month names, date order and digit grouping for the supported `--locale`s.
+//! A small lookup table rather than full CLDR data; en-US is the default.
+
+use crate::date::Date;
+
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum Locale {
+    #[default]
+    EnUs,
+    EnGb,
+    De,
+    Fr,
+    Es,
+    Ja,
+}
+
+impl std::str::FromStr for Locale {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace('_', "-").as_str() {
+            "en" | "en-us" => Ok(Locale::EnUs),
+            "en-gb" => Ok(Locale::EnGb),
+            "de" | "de-de" => Ok(Locale::De),
+            "fr" | "fr-fr" => Ok(Locale::Fr),
+            "es" | "es-es" => Ok(Locale::Es),
+            "ja" | "ja-jp" => Ok(Locale::Ja),
+            other => Err(format!("unknown locale: {} (expected en-US|en-GB|de-DE|fr-FR|es-ES|ja-JP)", other)),
+        }
+    }
+}
+
+impl Locale {
+    /// Abbreviated month name for a 1-based month.
+    pub fn month_abbr(&self, month: u32) -> &'static str {
+        let names: [&str; 12] = match self {
+            Locale::EnUs | Locale::EnGb => ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"],
+            Locale::De => ["Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez"],
+            Locale::Fr => ["janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.", "nov.", "déc."],
+            Locale::Es => ["ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sept", "oct", "nov", "dic"],
+            Locale::Ja => ["1月", "2月", "3月", "4月", "5月", "6月", "7月", "8月", "9月", "10月", "11月", "12月"],
+        };
+        names[(month.clamp(1, 12) - 1) as usize]
+    }
+
+    /// Abbreviated weekday name, 0 being Sunday as in `Date::weekday`.
+    pub fn weekday_abbr(&self, weekday: u32) -> &'static str {
+        let names: [&str; 7] = match self {
+            Locale::EnUs | Locale::EnGb => ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"],
+            Locale::De => ["So", "Mo", "Di", "Mi", "Do", "Fr", "Sa"],
+            Locale::Fr => ["dim.", "lun.", "mar.", "mer.", "jeu.", "ven.", "sam."],
+            Locale::Es => ["dom", "lun", "mar", "mié", "jue", "vie", "sáb"],
+            Locale::Ja => ["日", "月", "火", "水", "木", "金", "土"],
+        };
+        names[(weekday % 7) as usize]
+    }
+
+    fn group_separator(&self) -> &'static str {
+        match self {
+            Locale::EnUs | Locale::EnGb | Locale::Ja => ",",
+            Locale::De | Locale::Es => ".",
+            // Narrow no-break space, as French typography uses
+            Locale::Fr => "\u{202f}",
+        }
+    }
+
+    fn decimal_separator(&self) -> &'static str {
+        match self {
+            Locale::De | Locale::Fr | Locale::Es => ",",
+            Locale::EnUs | Locale::EnGb | Locale::Ja => ".",
+        }
+    }
+
+    /// Integer with thousands grouping, e.g. 1234567 -> "1,234,567" in en-US.
+    pub fn format_int(&self, n: i64) -> String {
+        let digits = n.unsigned_abs().to_string();
+        let mut out = String::new();
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                out.push_str(self.group_separator());
+            }
+            out.push(c);
+        }
+        if n < 0 { format!("-{}", out) } else { out }
+    }
+
+    /// Short form for tight spaces: 1234 -> "1.2k", 3400000 -> "3.4M"
+    /// (decimal comma where the locale uses one). Truncates rather than
+    /// rounds, so 999999 never reads as "1000k".
+    pub fn format_compact(&self, n: i64) -> String {
+        let abs = n.unsigned_abs();
+        let (scaled, suffix) = match abs {
+            0..=999 => return n.to_string(),
+            1_000..=999_999 => (abs / 100, "k"),
+            _ => (abs / 100_000, "M"),
+        };
+        let sign = if n < 0 { "-" } else { "" };
+        match scaled % 10 {
+            0 => format!("{}{}{}", sign, scaled / 10, suffix),
+            tenth => format!("{}{}{}{}{}", sign, scaled / 10, self.decimal_separator(), tenth, suffix),
+        }
+    }
+
+    /// A percentage given in tenths, e.g. 553 -> "55.3%".
+    pub fn format_percent(&self, tenths: u32) -> String {
+        format!("{}{}{}%", tenths / 10, self.decimal_separator(), tenths % 10)
+    }
+
+    pub fn format_date(&self, d: Date) -> String {
+        match self {
+            Locale::EnUs => format!("{} {}, {}", self.month_abbr(d.month), d.day, d.year),
+            Locale::EnGb | Locale::Fr | Locale::Es => format!("{:02}/{:02}/{}", d.day, d.month, d.year),
+            Locale::De => format!("{:02}.{:02}.{}", d.day, d.month, d.year),
+            Locale::Ja => format!("{}/{:02}/{:02}", d.year, d.month, d.day),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grouping_follows_locale() {
+        assert_eq!(Locale::EnUs.format_int(1234), "1,234");
+        assert_eq!(Locale::De.format_int(1234567), "1.234.567");
+        assert_eq!(Locale::EnUs.format_int(999), "999");
+        assert_eq!(Locale::EnUs.format_int(-1000), "-1,000");
+    }
+
+    #[test]
+    fn compact_numbers_abbreviate() {
+        assert_eq!(Locale::EnUs.format_compact(999), "999");
+        assert_eq!(Locale::EnUs.format_compact(1234), "1.2k");
+        assert_eq!(Locale::EnUs.format_compact(2000), "2k");
+        assert_eq!(Locale::EnUs.format_compact(999_999), "999.9k");
+        assert_eq!(Locale::De.format_compact(3_450_000), "3,4M");
+        assert_eq!(Locale::EnUs.format_percent(553), "55.3%");
+        assert_eq!(Locale::De.format_percent(1000), "100,0%");
+    }
+
+    #[test]
+    fn dates_and_months_follow_locale() {
+        let d = Date::new(2024, 3, 5).unwrap();
+        assert_eq!(Locale::EnUs.format_date(d), "Mar 5, 2024");
+        assert_eq!(Locale::De.format_date(d), "05.03.2024");
+        assert_eq!(Locale::Fr.month_abbr(2), "févr.");
+        assert_eq!(Locale::De.weekday_abbr(3), "Mi");
+    }
+}
@@ -1,221 +1,308 @@
 use dotenvy::dotenv;
-use serde::Deserialize;
-use std::collections::HashMap;
-use std::{env, f64::consts::PI};
-use svg::node::element::{Group, Path, Polygon, Text as SvgText};
-use svg::node::Text as TextNode;
-use svg::Document;
-
-// Larger canvas to prevent crowding
-const VIEW_WIDTH: f64 = 1400.0;
-const VIEW_HEIGHT: f64 = 1000.0;
-
-// --- GITHUB API STRUCTS ---
-#[derive(Deserialize, Debug)]
-struct GithubResponse { data: Data }
-#[derive(Deserialize, Debug)]
-struct Data { user: User }
-#[derive(Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-struct User {
-    contributions_collection: ContributionsCollection,
-    repositories: Repositories,
-}
-#[derive(Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-struct ContributionsCollection {
-    total_commit_contributions: i32,
-    total_issue_contributions: i32,
-    total_pull_request_contributions: i32,
-    total_pull_request_review_contributions: i32,
-    total_repository_contributions: i32,
-    contribution_calendar: ContributionCalendar,
-}
-#[derive(Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-struct ContributionCalendar {
-    total_contributions: i32,
-    weeks: Vec<Week>,
-}
-#[derive(Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-struct Week { contribution_days: Vec<Day> }
-#[derive(Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-struct Day { contribution_count: i32 }
-#[derive(Deserialize, Debug)]
-struct Repositories { nodes: Vec<RepoNode> }
-#[derive(Deserialize, Debug)]
-#[serde(rename_all = "camelCase")]
-struct RepoNode {
-    stargazer_count: i32,
-    fork_count: i32,
-    languages: Option<Languages>,
-}
-#[derive(Deserialize, Debug)]
-struct Languages { edges: Vec<LangEdge> }
-#[derive(Deserialize, Debug)]
-struct LangEdge { size: i32, node: LangNode }
-#[derive(Deserialize, Debug)]
-struct LangNode { name: String, color: Option<String> }
-
-// --- HELPERS ---
-
-fn project(x: f64, y: f64, z: f64) -> (f64, f64) {
-    let angle = 30.0_f64.to_radians();
-    // Increase multiplier to 20.0 for a much longer/wider "extended" look
-    let scale = 20.0; 
-    let sx = 400.0 + (x - y) * angle.cos() * scale;
-    let sy = 300.0 + (x + y) * angle.sin() * scale - z;
-    (sx, sy)
-}
-
-fn darken(hex: &str, amount: f64) -> String {
-    let hex = hex.trim_start_matches('#');
-    let r = (u8::from_str_radix(&hex[0..2], 16).unwrap_or(200) as f64 * amount) as u8;
-    let g = (u8::from_str_radix(&hex[2..4], 16).unwrap_or(200) as f64 * amount) as u8;
-    let b = (u8::from_str_radix(&hex[4..6], 16).unwrap_or(200) as f64 * amount) as u8;
-    format!("#{:02x}{:02x}{:02x}", r, g, b)
-}
-
-fn get_seasonal_color(week_idx: usize, count: i32) -> String {
-    if count == 0 { return "#ebedf0".to_string(); }
-    match week_idx {
-        0..=12  => "#c6e48b".to_string(), // Q1
-        13..=25 => "#f4e04d".to_string(), // Q2
-        26..=38 => "#a3a3a3".to_string(), // Q3
-        _       => "#d1a3d1".to_string(), // Q4
-    }
-}
-
-// --- DRAWING ---
-
-fn draw_3d_heatmap(weeks: &[Week]) -> Group {
-    let mut g = Group::new();
-    for (x, week) in weeks.iter().enumerate() {
-        for (y, day) in week.contribution_days.iter().enumerate() {
-            let h = (day.contribution_count as f64 * 5.0).max(2.0); // Taller bars
-            let (xf, yf) = (x as f64, y as f64);
-            let color = get_seasonal_color(x, day.contribution_count);
-
-            let p_top_back = project(xf, yf, h);
-            let p_top_left = project(xf + 1.0, yf, h);
-            let p_top_right = project(xf, yf + 1.0, h);
-            let p_top_front = project(xf + 1.0, yf + 1.0, h);
-            let p_bot_left = project(xf + 1.0, yf, 0.0);
-            let p_bot_right = project(xf, yf + 1.0, 0.0);
-            let p_bot_front = project(xf + 1.0, yf + 1.0, 0.0);
-
-            g = g.add(Polygon::new().set("fill", darken(&color, 0.8)).set("points", format!("{},{} {},{} {},{} {},{}", p_top_left.0, p_top_left.1, p_top_front.0, p_top_front.1, p_bot_front.0, p_bot_front.1, p_bot_left.0, p_bot_left.1)))
-                 .add(Polygon::new().set("fill", darken(&color, 0.6)).set("points", format!("{},{} {},{} {},{} {},{}", p_top_right.0, p_top_right.1, p_top_front.0, p_top_front.1, p_bot_front.0, p_bot_front.1, p_bot_right.0, p_bot_right.1)))
-                 .add(Polygon::new().set("fill", color.as_str()).set("points", format!("{},{} {},{} {},{} {},{}", p_top_back.0, p_top_back.1, p_top_left.0, p_top_left.1, p_top_front.0, p_top_front.1, p_top_right.0, p_top_right.1)));
-        }
+use heatmap_rust::annotate::unplaced_annotations;
+use heatmap_rust::cli::{parse_cli, parse_users, Cli, Command, USAGE};
+use heatmap_rust::demo::demo_user;
+use heatmap_rust::diff::baseline_from_user;
+use heatmap_rust::github::{ApiError, check_token, empty_edge_weeks, fetch_all_time, fetch_avatar, fetch_daily_breakdown, fetch_user, iter_days, user_request, Api, User};
+use heatmap_rust::merge::merge_users;
+use heatmap_rust::output::{daily_csv, save_card_streamed, save_file, save_svg, sparkline};
+use heatmap_rust::render::{background_warnings, check_canvas_size, explain_layout};
+use heatmap_rust::options::Source;
+use heatmap_rust::rest::fetch_rest_user;
+use heatmap_rust::stats::{profile_stats, summarize};
+use heatmap_rust::{render_profile, to_data_uri, Style};
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+// Reads --token-file / --token-stdin into `cli.token` once up front, so every
+// later lookup sees it. Errors name the source, never the contents.
+fn resolve_token_source(cli: &mut Cli) -> Result<(), Box<dyn Error>> {
+    let raw = if let Some(path) = &cli.token_file {
+        fs::read_to_string(path).map_err(|e| format!("reading token file {}: {}", path.display(), e))?
+    } else if cli.token_stdin {
+        let mut buf = String::new();
+        io::stdin().read_to_string(&mut buf).map_err(|e| format!("reading token from stdin: {}", e))?;
+        buf
+    } else {
+        return Ok(());
+    };
+    let token = raw.trim();
+    if token.is_empty() {
+        return Err("token source is empty".into());
+    }
+    cli.token = Some(token.to_string());
+    Ok(())
+}
+
+// The REST source also works unauthenticated, at a lower rate limit; an empty
+// token stands for none.
+fn token(cli: &Cli) -> Result<String, Box<dyn Error>> {
+    let token = cli.token.clone().or_else(|| env::var("GITHUB_TOKEN").ok());
+    match token {
+        Some(token) => Ok(token),
+        None if cli.options.source == Source::Rest => Ok(String::new()),
+        None => Err(ApiError::MissingToken.into()),
+    }
+}
+
+// Per-account token for --merge: GITHUB_TOKEN_<LOGIN> (uppercased, dashes as
+// underscores), e.g. from .env, falling back to the shared token.
+fn token_for(cli: &Cli, login: &str) -> Result<String, Box<dyn Error>> {
+    let key = format!("GITHUB_TOKEN_{}", login.to_uppercase().replace('-', "_"));
+    env::var(&key).or_else(|_| token(cli))
+}
+
+fn username(cli: &Cli) -> Result<String, Box<dyn Error>> {
+    Ok(cli.user.clone().or_else(|| env::var("GITHUB_USER").ok()).ok_or("no user: pass --user or set GITHUB_USER")?)
+}
+
+fn load_user(path: &Path) -> Result<User, Box<dyn Error>> {
+    let json = fs::read_to_string(path).map_err(|e| format!("reading {}: {}", path.display(), e))?;
+    Ok(serde_json::from_str(&json).map_err(|e| format!("parsing {}: {}", path.display(), e))?)
+}
+
+fn fetch_account(cli: &Cli, api: &Api, token: &str, login: &str) -> Result<User, Box<dyn Error>> {
+    if cli.options.source == Source::Rest {
+        return fetch_rest_user(api, Some(token).filter(|t| !t.is_empty()), login, cli.options.timezone.today(), cli.options.timezone);
     }
-    g
-}
-
-fn draw_donut_chart(lang_stats: HashMap<String, (i32, String)>) -> Group {
-    // Moved lower to (180, 820) to avoid heatmap overlap
-    let mut g = Group::new().set("transform", "translate(180, 820)");
-    let mut sorted_langs: Vec<_> = lang_stats.into_iter().collect();
-    sorted_langs.sort_by(|a, b| b.1.0.cmp(&a.1.0));
-    
-    let total: i32 = sorted_langs.iter().map(|v| v.1.0).sum();
-    let mut current_angle: f64 = 0.0;
-    let radius = 90.0;
-    let inner_radius = 60.0;
-
-    for (i, (name, (size, color))) in sorted_langs.iter().enumerate() {
-        let slice_angle = (*size as f64 / total as f64) * 2.0 * PI;
-        let x1 = current_angle.cos() * radius;
-        let y1 = current_angle.sin() * radius;
-        let x2 = (current_angle + slice_angle).cos() * radius;
-        let y2 = (current_angle + slice_angle).sin() * radius;
-        let x3 = (current_angle + slice_angle).cos() * inner_radius;
-        let y3 = (current_angle + slice_angle).sin() * inner_radius;
-        let x4 = current_angle.cos() * inner_radius;
-        let y4 = current_angle.sin() * inner_radius;
-
-        let large_arc = if slice_angle > PI { 1 } else { 0 };
-        let d = format!("M {} {} A {} {} 0 {} 1 {} {} L {} {} A {} {} 0 {} 0 {} {} Z", x1, y1, radius, radius, large_arc, x2, y2, x3, y3, inner_radius, inner_radius, large_arc, x4, y4);
-        g = g.add(Path::new().set("d", d).set("fill", color.as_str()));
-        
-        // Dynamic multi-column legend
-        let col = i / 8;
-        let row = i % 8;
-        let x_off = 120 + (col * 140);
-        let y_off = (row as i32 * 22) - 80;
-
-        g = g.add(Polygon::new().set("points", "0,0 12,0 12,12 0,12").set("fill", color.as_str()).set("transform", format!("translate({}, {})", x_off, y_off)));
-        g = g.add(SvgText::new().set("x", x_off + 18).set("y", y_off + 10).set("fill", "#586069").set("font-size", 14).add(TextNode::new(name)));
-        
-        current_angle += slice_angle;
-    }
-    g
-}
-
-fn draw_radar_chart(stats: &[i32; 5]) -> Group {
-    // Pushed far right and slightly up
-    let mut g = Group::new().set("transform", "translate(1150, 250)"); 
-    let labels = ["Commit", "Issue", "PullReq", "Review", "Repo"];
-    let max_r = 110.0;
-    
-    for r in [0.25, 0.5, 0.75, 1.0] {
-        let mut points = String::new();
-        for i in 0..5 {
-            let a = (i as f64 * 72.0 - 90.0).to_radians();
-            points.push_str(&format!("{},{} ", a.cos() * max_r * r, a.sin() * max_r * r));
+    let mut user = fetch_user(api, token, login)?;
+    if cli.options.all_time {
+        let since = user.created_at.ok_or("account creation date unavailable for --all-time")?;
+        user.contributions_collection = fetch_all_time(api, token, login, since, cli.options.timezone.today(), cli.options.timezone, cli.options.verbose)?;
+    } else if let Some(from) = cli.options.from {
+        let today = cli.options.timezone.today();
+        if from > today {
+            return Err(format!("--from {} is in the future", from).into());
         }
-        g = g.add(Polygon::new().set("points", points).set("fill", "none").set("stroke", "#e1e4e8"));
+        let until = cli.options.to.map_or(today, |to| to.min(today));
+        user.contributions_collection = fetch_all_time(api, token, login, from, until, cli.options.timezone, cli.options.verbose)?;
     }
+    Ok(user)
+}
 
-    let mut data_points = String::new();
-    for (i, &val) in stats.iter().enumerate() {
-        let val_scaled = (val as f64 + 1.0).log10() / 4.0; 
-        let a = (i as f64 * 72.0 - 90.0).to_radians();
-        let r = val_scaled.min(1.0) * max_r;
-        data_points.push_str(&format!("{},{} ", a.cos() * r, a.sin() * r));
-        g = g.add(SvgText::new().set("x", a.cos() * 140.0 - 25.0).set("y", a.sin() * 140.0).set("fill", "#586069").set("font-size", 15).add(TextNode::new(labels[i])));
+// --dump-query: the request as it would be sent, ready for GitHub's GraphQL
+// Explorer. The token only ever travels in the Authorization header.
+fn dump_query(cli: &Cli) -> Result<(), Box<dyn Error>> {
+    let logins = if cli.merge.is_empty() { vec![username(cli)?] } else { cli.merge.clone() };
+    eprintln!("POST {}\nAuthorization: bearer <redacted>", cli.api_url);
+    for login in logins {
+        eprintln!("{}", serde_json::to_string_pretty(&user_request(&login).build()?)?);
     }
-    g.add(Polygon::new().set("points", data_points).set("fill", "rgba(46, 160, 67, 0.2)").set("stroke", "#2ea043").set("stroke-width", 2))
+    if cli.options.all_time {
+        eprintln!("(--all-time then sends one calendar query per year from the account's createdAt)");
+    } else if cli.options.from.is_some() {
+        eprintln!("(--from/--year then sends one calendar query per calendar year of the range)");
+    }
+    Ok(())
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    dotenv().ok();
-    let token = env::var("GITHUB_TOKEN").expect("GITHUB_TOKEN required");
-    let username = env::var("GITHUB_USER").expect("GITHUB_USER required");
-
-    let client = reqwest::blocking::Client::new();
-    let query = r#"query($login:String!){user(login:$login){contributionsCollection{totalCommitContributions totalIssueContributions totalPullRequestContributions totalPullRequestReviewContributions totalRepositoryContributions contributionCalendar{totalContributions weeks{contributionDays{contributionCount}}}} repositories(first:100,ownerAffiliations:OWNER){nodes{stargazerCount forkCount languages(first:10,orderBy:{field:SIZE,direction:DESC}){edges{size node{name color}}}}}}}"#;
-
-    let res: GithubResponse = client.post("https://api.github.com/graphql").bearer_auth(token).header("User-Agent", "rust").json(&serde_json::json!({"query":query,"variables":{"login":username}})).send()?.json()?;
-    let user = res.data.user;
-
-    let mut langs = HashMap::new();
-    let mut total_stars = 0;
-    let mut total_forks = 0;
-    for repo in &user.repositories.nodes {
-        total_stars += repo.stargazer_count;
-        total_forks += repo.fork_count;
-        if let Some(l) = &repo.languages {
-            for edge in &l.edges {
-                let entry = langs.entry(edge.node.name.clone()).or_insert((0, edge.node.color.clone().unwrap_or("#cccccc".to_string())));
-                entry.0 += edge.size;
-            }
+fn fetch_profile(cli: &Cli) -> Result<User, Box<dyn Error>> {
+    if let Some(path) = &cli.input {
+        return load_user(path);
+    }
+    let api = Api::new(&cli.api_url, &cli.network)?;
+    let user = if cli.merge.is_empty() {
+        fetch_account(cli, &api, &token(cli)?, &username(cli)?)?
+    } else {
+        // Each account is folded in as it arrives, so only one raw response is
+        // held at a time
+        let mut merged: Option<User> = None;
+        for login in &cli.merge {
+            let user = fetch_account(cli, &api, &token_for(cli, login)?, login)?;
+            merged = Some(merge_users(merged.into_iter().chain([user]).collect()));
         }
+        merged.ok_or("--merge needs at least one login")?
+    };
+    report_fetch(cli, &user)?;
+    Ok(user)
+}
+
+// What every fetched card goes through before drawing: --save-data and the
+// --verbose summary of what came back.
+fn report_fetch(cli: &Cli, user: &User) -> Result<(), Box<dyn Error>> {
+    if let Some(path) = &cli.save_data {
+        save_file(path, serde_json::to_string(user)?)?;
+    }
+    if cli.options.verbose {
+        eprintln!("{}", summarize(user));
+    }
+    Ok(())
+}
+
+// --color-by needs a second query for the secondary series, so it only
+// applies to a live fetch of one account. The series spans the same dates as
+// the fetched calendar, whether that is the trailing year, --from/--to or
+// --all-time.
+fn load_color_series(cli: &mut Cli, user: &User) -> Result<(), Box<dyn Error>> {
+    let Some(metric) = cli.options.color_by else { return Ok(()) };
+    if cli.command != Command::Render || cli.input.is_some() || !cli.merge.is_empty() {
+        eprintln!("warning: --color-by needs a live fetch of a single account; using the regular colors");
+        return Ok(());
+    }
+    let mut dates = iter_days(&user.contributions_collection.contribution_calendar.weeks).filter_map(|(_, d)| d.date);
+    let Some(first) = dates.next() else { return Ok(()) };
+    let (since, until) = dates.fold((first, first), |(lo, hi), d| (lo.min(d), hi.max(d)));
+    let api = Api::new(&cli.api_url, &cli.network)?;
+    cli.options.color_series = Some(fetch_daily_breakdown(&api, &token(cli)?, &user.login, metric, since, until, cli.options.timezone)?);
+    Ok(())
+}
+
+// --show-avatar embeds the image so the card still shows it where external
+// images are blocked, such as GitHub's image proxy. If the download fails the
+// card links the URL instead.
+fn embed_avatar(cli: &mut Cli, user: &User) {
+    let Some(url) = user.avatar_url.as_deref().filter(|_| cli.options.show_avatar) else { return };
+    match Api::new(&cli.api_url, &cli.network).map_err(Into::into).and_then(|api| fetch_avatar(&api, url)) {
+        Ok(uri) => cli.options.avatar = Some(uri),
+        Err(e) => eprintln!("warning: couldn't download the avatar ({}); linking it instead", e),
+    }
+}
+
+fn write_card(user: &User, cli: &Cli) -> Result<(), Box<dyn Error>> {
+    if let Some(path) = &cli.csv {
+        save_file(path, daily_csv(&user.contributions_collection.contribution_calendar.weeks))?;
+        println!("Generated: {}", path.display());
+        return Ok(());
+    }
+    if cli.options.style == Style::Sparkline {
+        println!("{}", sparkline(&user.contributions_collection.contribution_calendar.weeks));
+        return Ok(());
+    }
+
+    check_canvas_size(&user.contributions_collection.contribution_calendar.weeks, &cli.options)?;
+    if cli.options.trim_empty_edges && cli.options.verbose {
+        let (leading, trailing) = empty_edge_weeks(&user.contributions_collection.contribution_calendar.weeks);
+        eprintln!("--trim-empty-edges: dropped {} leading and {} trailing empty weeks", leading, trailing);
+    }
+    for warning in background_warnings(&cli.options) {
+        eprintln!("warning: {}", warning);
+    }
+    for a in unplaced_annotations(&user.contributions_collection.contribution_calendar.weeks, &cli.options) {
+        eprintln!("warning: skipping --annotate {}: date is outside the calendar", a.date);
+    }
+    if cli.options.explain {
+        eprintln!("{}", explain_layout(user, &cli.options));
+        return Ok(());
+    }
+    let ext = if cli.gzip { "svgz" } else { "svg" };
+    for preset in &cli.presets {
+        let path = format!("github_extended_{}.{}", preset.name, ext);
+        save_svg(&path, &render_profile(user, &preset.apply(&cli.options)))?;
+        println!("Generated: {}", path);
+    }
+    if !cli.presets.is_empty() {
+        return Ok(());
+    }
+
+    if cli.options.emit_data_uri {
+        println!("{}", to_data_uri(&render_profile(user, &cli.options)));
+        return Ok(());
     }
 
-    let mut doc = Document::new().set("viewBox", (0, 0, VIEW_WIDTH, VIEW_HEIGHT)).set("style", "background:#ffffff; font-family: sans-serif;");
-    
-    doc = doc.add(draw_3d_heatmap(&user.contributions_collection.contribution_calendar.weeks));
-    doc = doc.add(draw_donut_chart(langs));
-    doc = doc.add(draw_radar_chart(&[user.contributions_collection.total_commit_contributions, user.contributions_collection.total_issue_contributions, user.contributions_collection.total_pull_request_contributions, user.contributions_collection.total_pull_request_review_contributions, user.contributions_collection.total_repository_contributions]));
+    let path = cli.output.clone().unwrap_or_else(|| format!("github_extended_no_overlap.{}", ext).into());
+    if cli.merge.is_empty() {
+        save_svg(&path, &render_profile(user, &cli.options))?;
+    } else {
+        // A merged card can cover many accounts; write it panel by panel
+        // rather than holding the whole document
+        save_card_streamed(&path, user, &cli.options)?;
+    }
+    println!("Generated: {}", path.display());
+    Ok(())
+}
 
-    // Footer - Placed at safe bottom
-    let footer_text = format!("{} contributions    ⭐ {}     {}", user.contributions_collection.contribution_calendar.total_contributions, total_stars, total_forks);
-    doc = doc.add(SvgText::new().set("x", VIEW_WIDTH / 2.0).set("y", VIEW_HEIGHT - 40.0).set("fill", "#586069").set("text-anchor", "middle").set("font-size", 24).set("font-weight", "bold").add(TextNode::new(footer_text)));
+// --users-file: one card per login through a shared client and token, each
+// written by the single-card path under <out-dir>/<login>.svg[z]. A failed
+// account is reported and skipped; the run fails at the end if any did.
+fn render_batch(cli: &Cli, path: &Path) -> Result<(), Box<dyn Error>> {
+    let text = fs::read_to_string(path).map_err(|e| format!("reading {}: {}", path.display(), e))?;
+    let logins = parse_users(&text).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let api = Api::new(&cli.api_url, &cli.network)?;
+    let token = token(cli)?;
+    let ext = if cli.gzip { "svgz" } else { "svg" };
+    let mut failed = Vec::new();
+    for login in &logins {
+        let mut card = cli.clone();
+        card.user = Some(login.clone());
+        card.output = Some(cli.out_dir.join(format!("{}.{}", login, ext)));
+        let result = fetch_account(cli, &api, &token, login).and_then(|user| {
+            report_fetch(&card, &user)?;
+            load_color_series(&mut card, &user)?;
+            embed_avatar(&mut card, &user);
+            write_card(&user, &card)
+        });
+        if let Err(e) = result {
+            eprintln!("error: {}: {}", login, e);
+            failed.push(login.as_str());
+        }
+    }
+    println!("{} of {} cards generated", logins.len() - failed.len(), logins.len());
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("failed for {}", failed.join(", ")).into())
+    }
+}
 
-    svg::save("github_extended_no_overlap.svg", &doc)?;
-    println!("Generated: github_extended_no_overlap.svg");
+fn run_check(cli: &Cli) -> Result<(), Box<dyn Error>> {
+    let (login, rate) = check_token(&Api::new(&cli.api_url, &cli.network)?, &token(cli)?)?;
+    println!("Token OK: authenticated as {} ({}/{} requests left, resets {})", login, rate.remaining, rate.limit, rate.reset_at);
     Ok(())
 }
+
+fn run() -> Result<(), Box<dyn Error>> {
+    dotenv().ok();
+    let mut cli = parse_cli(env::args().skip(1))?;
+    resolve_token_source(&mut cli)?;
+    if let Some(path) = &cli.diff_against {
+        cli.options.baseline = Some(baseline_from_user(&load_user(path)?));
+    }
+
+    if cli.dump_query {
+        return dump_query(&cli);
+    }
+    if cli.options.source == Source::Rest {
+        if cli.options.all_time || cli.options.from.is_some() {
+            return Err("--all-time, --from and --year need the GraphQL source; the REST events API only reaches back 90 days".into());
+        }
+        eprintln!("warning: --source rest approximates the calendar from recent public events; private and older activity is missing");
+    }
+
+    match cli.command {
+        Command::Render => {
+            if let Some(path) = &cli.users_file {
+                return render_batch(&cli, path);
+            }
+            let user = fetch_profile(&cli)?;
+            load_color_series(&mut cli, &user)?;
+            embed_avatar(&mut cli, &user);
+            write_card(&user, &cli)
+        }
+        Command::Stats => {
+            println!("{}", serde_json::to_string_pretty(&profile_stats(&fetch_profile(&cli)?, cli.options.timezone.today()))?);
+            Ok(())
+        }
+        Command::Check => run_check(&cli),
+        Command::Demo => {
+            let user = demo_user();
+            load_color_series(&mut cli, &user)?;
+            write_card(&user, &cli)
+        }
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+    }
+}
+
+// Errors are printed with Display so their context and hints reach the user
+// instead of a Debug dump.
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,98 @@
+//! `--merge`: several accounts folded into one dataset, rendered as a single
+//! card. Daily counts are summed by date; totals, repositories and languages
+//! are pooled.
+
+use crate::date::Date;
+use crate::github::{iter_days, ContributionCalendar, ContributionsCollection, Day, Repositories, User, Week};
+use std::collections::BTreeMap;
+
+// Lays dated counts out as Sunday-first weeks, filling gaps between the first
+// and last date with empty days so the calendar stays continuous.
+pub(crate) fn weeks_from_days(days: &BTreeMap<Date, i32>) -> Vec<Week> {
+    let (Some(&first), Some(&last)) = (days.keys().next(), days.keys().next_back()) else { return Vec::new() };
+    let mut weeks: Vec<Week> = Vec::new();
+    let mut date = first;
+    while date <= last {
+        if weeks.is_empty() || date.weekday() == 0 {
+            weeks.push(Week { contribution_days: Vec::new() });
+        }
+        let day = Day { contribution_count: days.get(&date).copied().unwrap_or(0), date: Some(date) };
+        weeks.last_mut().expect("pushed above").contribution_days.push(day);
+        date = date.add_days(1);
+    }
+    weeks
+}
+
+/// Merges profiles into one. Dates present in only some accounts are kept
+/// as-is; undated days can't be lined up and are dropped.
+pub fn merge_users(users: Vec<User>) -> User {
+    let mut days: BTreeMap<Date, i32> = BTreeMap::new();
+    let mut totals = ContributionsCollection {
+        total_commit_contributions: 0,
+        total_issue_contributions: 0,
+        total_pull_request_contributions: 0,
+        total_pull_request_review_contributions: 0,
+        total_repository_contributions: 0,
+        contribution_calendar: ContributionCalendar { total_contributions: 0, weeks: Vec::new() },
+    };
+    let mut nodes = Vec::new();
+    let mut created_at: Option<Date> = None;
+    let mut logins = Vec::new();
+    for user in users {
+        logins.push(user.login);
+        let c = user.contributions_collection;
+        for (_, day) in iter_days(&c.contribution_calendar.weeks) {
+            if let Some(date) = day.date {
+                *days.entry(date).or_insert(0) += day.contribution_count;
+            }
+        }
+        totals.total_commit_contributions += c.total_commit_contributions;
+        totals.total_issue_contributions += c.total_issue_contributions;
+        totals.total_pull_request_contributions += c.total_pull_request_contributions;
+        totals.total_pull_request_review_contributions += c.total_pull_request_review_contributions;
+        totals.total_repository_contributions += c.total_repository_contributions;
+        totals.contribution_calendar.total_contributions += c.contribution_calendar.total_contributions;
+        nodes.extend(user.repositories.nodes);
+        created_at = match (created_at, user.created_at) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+    totals.contribution_calendar.weeks = weeks_from_days(&days);
+    User { login: logins.join(" + "), name: None, avatar_url: None, created_at, contributions_collection: totals, repositories: Repositories { nodes, page_info: None } }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(days: &[(&str, i32)]) -> User {
+        let days: Vec<_> = days.iter().map(|(date, count)| serde_json::json!({ "date": date, "contributionCount": count })).collect();
+        let total: i32 = days.iter().map(|d| d["contributionCount"].as_i64().unwrap() as i32).sum();
+        serde_json::from_value(serde_json::json!({
+            "contributionsCollection": {
+                "totalCommitContributions": 1, "totalIssueContributions": 0,
+                "totalPullRequestContributions": 0, "totalPullRequestReviewContributions": 0,
+                "totalRepositoryContributions": 0,
+                "contributionCalendar": { "totalContributions": total, "weeks": [{ "contributionDays": days }] }
+            },
+            "repositories": { "nodes": [{ "stargazerCount": 2, "forkCount": 0, "languages": null }] }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn merged_days_are_summed_by_date() {
+        // 2025-01-04 is a Saturday, so the merged calendar wraps into a second week
+        let work = user(&[("2025-01-03", 2), ("2025-01-04", 1)]);
+        let personal = user(&[("2025-01-04", 3), ("2025-01-06", 5)]);
+        let merged = merge_users(vec![work, personal]);
+
+        let days: Vec<(String, i32)> = iter_days(&merged.contributions_collection.contribution_calendar.weeks).map(|(_, d)| (d.date.unwrap().to_string(), d.contribution_count)).collect();
+        assert_eq!(days, [("2025-01-03", 2), ("2025-01-04", 4), ("2025-01-05", 0), ("2025-01-06", 5)].map(|(d, c)| (d.to_string(), c)));
+        assert_eq!(merged.contributions_collection.contribution_calendar.weeks.len(), 2);
+        assert_eq!(merged.contributions_collection.contribution_calendar.total_contributions, 11);
+        assert_eq!(merged.contributions_collection.total_commit_contributions, 2);
+        assert_eq!(merged.repositories.nodes.len(), 2);
+    }
+}
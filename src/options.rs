@@ -0,0 +1,826 @@
+//! Render options and the command-line flags that set them.
+
+use crate::annotate::Annotation;
+use crate::date::{Date, UtcOffset};
+use crate::diff::Baseline;
+use crate::github::DailyCounts;
+use crate::locale::Locale;
+use crate::render::{Projection, DEFAULT_BAR_MAX, DEFAULT_BAR_SCALE, DEFAULT_CELL_RADIUS, DEFAULT_INTENSITY_LEVELS, MAX_TOP_DAYS, DEFAULT_MAX_CANVAS_BARS, DEFAULT_OTHER_BELOW, DEFAULT_PANEL_GAP, DEFAULT_RADAR_BENCHMARK};
+use crate::theme::{is_hex_color, Theme};
+use svg::node::element::Element;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Style {
+    Bars,
+    // Experimental: interpolated isometric mesh instead of discrete cubes
+    Surface,
+    // GitHub-style calendar cells: rounded squares on the ground plane
+    Flat,
+    // One line of block characters printed to stdout instead of a card;
+    // card renderers draw it as Bars
+    Sparkline,
+}
+
+impl std::str::FromStr for Style {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bar" | "bars" => Ok(Style::Bars),
+            "surface" => Ok(Style::Surface),
+            "flat" => Ok(Style::Flat),
+            "sparkline" => Ok(Style::Sparkline),
+            other => Err(format!("unknown style: {} (expected bar|surface|flat|sparkline)", other)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum HeatmapLayout {
+    Grid,
+    // Experimental: concentric month rings, one segment per day
+    Rings,
+}
+
+impl std::str::FromStr for HeatmapLayout {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "grid" => Ok(HeatmapLayout::Grid),
+            "rings" | "spiral" => Ok(HeatmapLayout::Rings),
+            other => Err(format!("unknown layout: {} (expected grid|rings)", other)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Granularity {
+    Day,
+    // One cell per week, sized and colored by the week's total
+    Week,
+}
+
+impl std::str::FromStr for Granularity {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "day" => Ok(Granularity::Day),
+            "week" => Ok(Granularity::Week),
+            other => Err(format!("unknown granularity: {} (expected day|week)", other)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum WeekStart {
+    // GitHub's own layout
+    #[default]
+    Sunday,
+    Monday,
+}
+
+impl std::str::FromStr for WeekStart {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sunday" => Ok(WeekStart::Sunday),
+            "monday" => Ok(WeekStart::Monday),
+            other => Err(format!("unknown week start: {} (expected sunday|monday)", other)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ColorMode {
+    // Fixed palette per quarter of the year
+    #[default]
+    Seasonal,
+    // Continuous blend along --gradient-stops by count
+    Gradient,
+    // GitHub-style discrete levels at quantiles of the nonzero counts
+    Intensity,
+}
+
+impl std::str::FromStr for ColorMode {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "seasonal" => Ok(ColorMode::Seasonal),
+            "gradient" => Ok(ColorMode::Gradient),
+            "intensity" => Ok(ColorMode::Intensity),
+            other => Err(format!("unknown color mode: {} (expected seasonal|gradient|intensity)", other)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum LangWeight {
+    // Share of bytes across all repos
+    #[default]
+    Bytes,
+    // One vote per repo for its primary language
+    Repos,
+}
+
+impl std::str::FromStr for LangWeight {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bytes" => Ok(LangWeight::Bytes),
+            "repos" => Ok(LangWeight::Repos),
+            other => Err(format!("unknown language weighting: {} (expected bytes|repos)", other)),
+        }
+    }
+}
+
+/// Secondary daily metric for `--color-by`: bar height keeps the total
+/// contribution count while the fill follows this series.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorMetric {
+    PullRequests,
+    Issues,
+    Reviews,
+}
+
+impl ColorMetric {
+    /// The `contributionsCollection` connection holding this metric's events.
+    pub fn graphql_field(self) -> &'static str {
+        match self {
+            ColorMetric::PullRequests => "pullRequestContributions",
+            ColorMetric::Issues => "issueContributions",
+            ColorMetric::Reviews => "pullRequestReviewContributions",
+        }
+    }
+}
+
+impl std::str::FromStr for ColorMetric {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "prs" => Ok(ColorMetric::PullRequests),
+            "issues" => Ok(ColorMetric::Issues),
+            "reviews" => Ok(ColorMetric::Reviews),
+            other => Err(format!("unknown color metric: {} (expected prs|issues|reviews)", other)),
+        }
+    }
+}
+
+/// Which way the isometric grid runs across the card.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Orientation {
+    // Weeks run down to the right, panels left-bottom and right-top
+    Standard,
+    // Left-right mirror image, see `Projection::mirrored`
+    Mirrored,
+}
+
+impl std::str::FromStr for Orientation {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "standard" => Ok(Orientation::Standard),
+            "mirrored" => Ok(Orientation::Mirrored),
+            other => Err(format!("unknown orientation: {} (expected standard|mirrored)", other)),
+        }
+    }
+}
+
+/// Where profile data comes from.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Source {
+    #[default]
+    Graphql,
+    // Recent public events from the REST API; see `rest`
+    Rest,
+}
+
+impl std::str::FromStr for Source {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "graphql" => Ok(Source::Graphql),
+            "rest" => Ok(Source::Rest),
+            other => Err(format!("unknown source: {} (expected graphql|rest)", other)),
+        }
+    }
+}
+
+/// `--line-scale`: how stroke widths are multiplied.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineScale {
+    Factor(f64),
+    // Enough to offset `size` shrinking the canvas, never below 1
+    Auto,
+}
+
+impl Default for LineScale {
+    fn default() -> Self {
+        LineScale::Factor(1.0)
+    }
+}
+
+impl std::str::FromStr for LineScale {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "auto" {
+            return Ok(LineScale::Auto);
+        }
+        match s.parse::<f64>() {
+            Ok(f) if f.is_finite() && f > 0.0 && f <= 10.0 => Ok(LineScale::Factor(f)),
+            _ => Err(format!("invalid --line-scale: {} (expected auto or a factor in (0, 10])", s)),
+        }
+    }
+}
+
+/// `--height-mode`: how a day's count becomes a bar height.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum HeightMode {
+    // Fixed pixels per contribution
+    #[default]
+    Linear,
+    // Share of the busiest drawn day, which always reaches full height
+    Normalized,
+}
+
+impl std::str::FromStr for HeightMode {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "linear" => Ok(HeightMode::Linear),
+            "normalized" => Ok(HeightMode::Normalized),
+            other => Err(format!("unknown height mode: {} (expected linear|normalized)", other)),
+        }
+    }
+}
+
+/// `--height-curve`: how counts are compressed before scaling to a height,
+/// so outlier days don't dwarf the rest.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum HeightCurve {
+    #[default]
+    Linear,
+    Sqrt,
+    // log2(1 + count), so a single contribution still maps to 1
+    Log,
+}
+
+impl HeightCurve {
+    pub fn apply(self, count: f64) -> f64 {
+        let count = count.max(0.0);
+        match self {
+            HeightCurve::Linear => count,
+            HeightCurve::Sqrt => count.sqrt(),
+            HeightCurve::Log => count.ln_1p() / std::f64::consts::LN_2,
+        }
+    }
+}
+
+impl std::str::FromStr for HeightCurve {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "linear" => Ok(HeightCurve::Linear),
+            "sqrt" => Ok(HeightCurve::Sqrt),
+            "log" => Ok(HeightCurve::Log),
+            other => Err(format!("unknown height curve: {} (expected linear|sqrt|log)", other)),
+        }
+    }
+}
+
+/// A single panel rendered as its own card by `--only`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OnlyPanel {
+    Donut,
+}
+
+impl std::str::FromStr for OnlyPanel {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "donut" => Ok(OnlyPanel::Donut),
+            other => Err(format!("unknown panel for --only: {} (expected donut)", other)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AxisLabels {
+    #[default]
+    Month,
+    // ISO week numbers, on every few columns
+    Week,
+    None,
+}
+
+impl std::str::FromStr for AxisLabels {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "month" => Ok(AxisLabels::Month),
+            "week" => Ok(AxisLabels::Week),
+            "none" => Ok(AxisLabels::None),
+            other => Err(format!("unknown axis labels: {} (expected week|month|none)", other)),
+        }
+    }
+}
+
+/// What fills the canvas behind the card.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Background {
+    // The theme's background color
+    #[default]
+    Flat,
+    // Top-to-bottom linear gradient through these colors
+    Gradient(Vec<String>),
+    // Image URL scaled to cover the canvas
+    Image(String),
+}
+
+/// Which side panels and labels a card includes; the heatmap is always drawn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Panels {
+    pub donut: bool,
+    pub radar: bool,
+    pub footer: bool,
+    /// Repositories listed with their primary language; off by default.
+    pub repo_langs: bool,
+}
+
+impl Default for Panels {
+    fn default() -> Self {
+        Panels { donut: true, radar: true, footer: true, repo_langs: false }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Options {
+    pub donut_stroke: bool,
+    /// Smallest drawn donut slice, in degrees; `None` draws true proportions.
+    pub min_slice_angle: Option<f64>,
+    pub projection: Projection,
+    pub style: Style,
+    pub layout: HeatmapLayout,
+    /// Grid cells per day or per week. Weekly cells are always drawn as bars
+    /// and skip the --diff-against highlight, which is tracked per day.
+    pub granularity: Granularity,
+    /// Refuse to render more heatmap cells than this (see `check_canvas_size`).
+    pub max_canvas_bars: usize,
+    /// First day of the week, i.e. the top row of the bar grid.
+    pub week_start: WeekStart,
+    pub legend_cols: Option<usize>,
+    /// Languages under this share of the donut, in percent, are drawn as one
+    /// "Other" slice; 0 keeps every language.
+    pub other_below: f64,
+    /// Append each language's size (e.g. "12.3 KB") to its legend row.
+    pub legend_sizes: bool,
+    /// CSS font-family list for all text. SVG viewers resolve it against their
+    /// installed fonts, so an unavailable family silently falls back to the
+    /// generic `sans-serif` appended by `font_family_css`. Rasterized output has
+    /// no viewer to resolve against and needs the font file itself.
+    pub font_family: String,
+    /// Caps the count used for bar heights; true counts stay in the tooltips.
+    pub clamp_count: Option<i32>,
+    /// Daily contribution target: days reaching it get their own color and a
+    /// translucent plane marks its height across the bars.
+    pub goal: Option<i32>,
+    /// Progress ring in the donut's hole: the calendar's total against
+    /// `goal` for every day it covers. Requires `goal`.
+    pub goal_ring: bool,
+    /// Outline the calendar's last N days.
+    pub highlight_recent: Option<usize>,
+    /// With `highlight_recent`, wash out the days before the window.
+    pub dim_older: bool,
+    /// Minimum bar height in pixels for days with at least one contribution.
+    pub min_height: f64,
+    pub height_mode: HeightMode,
+    pub height_curve: HeightCurve,
+    /// Pixels per unit of the curved count under `HeightMode::Linear`.
+    pub bar_scale: f64,
+    /// Tallest a bar is drawn, in pixels, so a runaway day can't leave the
+    /// canvas; `None` (`--bar-max none`) lets bars grow freely.
+    pub bar_max: Option<f64>,
+    /// Busiest drawn count that `HeightMode::Normalized` scales against,
+    /// filled in by `render_profile` from the calendar as drawn.
+    pub height_max: i32,
+    /// Corner radius of `--style flat` cells as a fraction of the cell size.
+    pub cell_radius: f64,
+    /// Multiplier for grid, outline and separator stroke widths, so thin
+    /// lines survive being drawn small.
+    pub line_scale: LineScale,
+    /// Print the card as a `data:` URI instead of writing a file.
+    pub emit_data_uri: bool,
+    /// Print the computed layout to stderr instead of writing a file.
+    pub explain: bool,
+    /// Embed the daily series and stats as JSON in a `<metadata>` element.
+    pub embed_data: bool,
+    /// Title the card with the display name, falling back to the login.
+    pub show_name: bool,
+    /// Draw the profile picture, clipped to a circle, in the title row.
+    pub show_avatar: bool,
+    /// The avatar as a `data:` URI, filled in by the caller after fetching it;
+    /// `None` links the profile's `avatar_url` instead.
+    pub avatar: Option<String>,
+    /// Fetch every year since the account was created instead of the trailing year.
+    pub all_time: bool,
+    /// First day of the calendar (`--from`, `--year`) instead of a year ago.
+    pub from: Option<Date>,
+    /// Last day of the calendar with `from`; today when `None`.
+    pub to: Option<Date>,
+    /// With `Source::Rest` the card is marked as approximate.
+    pub source: Source,
+    /// Offset used for request day boundaries, "today" and event
+    /// timestamps; UTC unless `--timezone` is given.
+    pub timezone: UtcOffset,
+    pub verbose: bool,
+    /// Embed a hover script with per-bar date/count data. Browser-only: viewers
+    /// that don't run scripts (such as `<img>` embeds) show the static card.
+    pub interactive: bool,
+    /// Month names, date order and number grouping in labels.
+    pub locale: Locale,
+    /// Snapshot to diff against; days with new activity are highlighted.
+    pub baseline: Option<Baseline>,
+    pub theme: Theme,
+    /// Fill opacity of the radar polygon; the stroke stays opaque.
+    pub radar_opacity: f64,
+    /// Per-axis reference values the radar is scaled against; `None` keeps
+    /// the default log scale.
+    pub radar_benchmark: Option<[i32; 5]>,
+    /// Extra elements (filters, patterns, gradients) for the `<defs>` block.
+    pub extra_defs: Vec<Element>,
+    /// Id of a def (e.g. a filter from `extra_defs`) applied to the bar group.
+    pub bar_filter: Option<String>,
+    /// Built-in soft drop shadow under the bars.
+    pub shadow: bool,
+    /// Inset the bar tops with a small chamfer for a softer look.
+    pub bevel: bool,
+    /// Pixels between the donut and its legend; the side panels shift by the
+    /// same amount relative to the default.
+    pub panel_gap: f64,
+    /// Draw the bar-height legend next to the heatmap.
+    pub legend: bool,
+    /// Draw a separator every this many weeks under the bars; 0 disables.
+    pub week_ticks: usize,
+    /// List this many of the busiest days beside the heatmap; 0 disables.
+    pub top_days: usize,
+    /// Drop all-zero weeks before the first and after the last contribution.
+    pub trim_empty_edges: bool,
+    /// Mark each January 1 with a separator and the year, for multi-year views.
+    pub year_separators: bool,
+    /// Milestone markers drawn above their day's bar.
+    pub annotations: Vec<Annotation>,
+    /// Favicon-sized preset: only the heatmap, as flat cells in a square.
+    pub mini: bool,
+    /// Render just this panel on a canvas cropped to it.
+    pub only: Option<OnlyPanel>,
+    pub color_mode: ColorMode,
+    /// Levels for `--color-mode intensity`, counting the empty level.
+    pub intensity_levels: usize,
+    /// Swap the seasonal, intensity and donut palettes for ones that stay
+    /// distinguishable with color vision deficiency; see `render::CVD_QUALITATIVE`.
+    pub cvd_safe: bool,
+    /// Two or three colors from count 0 to the busiest day, for the gradient
+    /// mode. Empty uses the default green ramp, starting from the theme's
+    /// `empty_cell`.
+    pub gradient_stops: Vec<String>,
+    /// How the donut weighs languages.
+    pub lang_weight: LangWeight,
+    /// With `--color-by`, bar height is the day's total contributions and the
+    /// fill is `color_series` along `gradient_stops`.
+    pub color_by: Option<ColorMetric>,
+    /// Daily counts of the `color_by` metric, filled in by the caller after
+    /// fetching them; `None` keeps the regular coloring.
+    pub color_series: Option<DailyCounts>,
+    pub panels: Panels,
+    /// Force the small, abbreviated footer; it is also used automatically
+    /// when `size` is narrower than `COMPACT_FOOTER_BELOW`.
+    pub compact_footer: bool,
+    /// Column labels along the back edge of the grid; unless `None`, the
+    /// Monday, Wednesday and Friday rows are labelled too.
+    pub axis_labels: AxisLabels,
+    pub background: Background,
+    /// Output width and height in pixels; the card scales to fit, keeping its
+    /// aspect ratio. `None` leaves sizing to the viewer.
+    pub size: Option<(u32, u32)>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            donut_stroke: true,
+            min_slice_angle: None,
+            projection: Projection::default(),
+            style: Style::Bars,
+            layout: HeatmapLayout::Grid,
+            granularity: Granularity::Day,
+            max_canvas_bars: DEFAULT_MAX_CANVAS_BARS,
+            week_start: WeekStart::default(),
+            legend_cols: None,
+            other_below: DEFAULT_OTHER_BELOW,
+            legend_sizes: false,
+            font_family: "sans-serif".to_string(),
+            clamp_count: None,
+            goal: None,
+            goal_ring: false,
+            highlight_recent: None,
+            dim_older: false,
+            min_height: 4.0,
+            height_mode: HeightMode::default(),
+            height_curve: HeightCurve::default(),
+            bar_scale: DEFAULT_BAR_SCALE,
+            bar_max: Some(DEFAULT_BAR_MAX),
+            height_max: 0,
+            cell_radius: DEFAULT_CELL_RADIUS,
+            line_scale: LineScale::default(),
+            emit_data_uri: false,
+            explain: false,
+            embed_data: false,
+            show_name: false,
+            show_avatar: false,
+            avatar: None,
+            all_time: false,
+            from: None,
+            to: None,
+            source: Source::default(),
+            timezone: UtcOffset::UTC,
+            verbose: false,
+            interactive: false,
+            locale: Locale::default(),
+            baseline: None,
+            theme: Theme::default(),
+            radar_opacity: 0.2,
+            radar_benchmark: None,
+            extra_defs: Vec::new(),
+            bar_filter: None,
+            shadow: false,
+            bevel: false,
+            panel_gap: DEFAULT_PANEL_GAP,
+            legend: true,
+            week_ticks: 0,
+            top_days: 0,
+            trim_empty_edges: false,
+            year_separators: false,
+            annotations: Vec::new(),
+            mini: false,
+            only: None,
+            color_mode: ColorMode::default(),
+            intensity_levels: DEFAULT_INTENSITY_LEVELS,
+            cvd_safe: false,
+            gradient_stops: Vec::new(),
+            lang_weight: LangWeight::default(),
+            color_by: None,
+            color_series: None,
+            panels: Panels::default(),
+            compact_footer: false,
+            axis_labels: AxisLabels::default(),
+            background: Background::default(),
+            size: None,
+        }
+    }
+}
+
+impl Options {
+    /// Adds an element to the document's `<defs>`.
+    pub fn with_def<T: Into<Element>>(mut self, def: T) -> Self {
+        self.extra_defs.push(def.into());
+        self
+    }
+
+    /// Drops the stats footer; the canvas loses the strip it occupied.
+    pub fn without_footer(mut self) -> Self {
+        self.panels.footer = false;
+        self
+    }
+
+    /// References a def by id as the bar group's `filter`.
+    pub fn with_bar_filter(mut self, id: &str) -> Self {
+        self.bar_filter = Some(id.to_string());
+        self
+    }
+
+    /// The font-family value for the root style, stripped of characters that
+    /// could break out of the declaration and always ending in a generic family.
+    pub fn font_family_css(&self) -> String {
+        let cleaned: String = self.font_family.chars().filter(|c| !matches!(c, ';' | '{' | '}' | '<' | '>' | '"' | '\\')).collect();
+        let cleaned = cleaned.trim().trim_end_matches(',').trim();
+        let generic = ["serif", "sans-serif", "monospace", "cursive", "fantasy", "system-ui"];
+        let has_generic = cleaned.rsplit(',').next().is_some_and(|last| generic.contains(&last.trim()));
+        match (cleaned.is_empty(), has_generic) {
+            (true, _) => "sans-serif".to_string(),
+            (false, true) => cleaned.to_string(),
+            (false, false) => format!("{}, sans-serif", cleaned),
+        }
+    }
+}
+
+pub(crate) fn next_value<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<String, String> {
+    args.next().ok_or_else(|| format!("{} requires a value", flag))
+}
+
+fn parse_value<T, I>(args: &mut I, flag: &str) -> Result<T, String>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+    I: Iterator<Item = String>,
+{
+    next_value(args, flag)?.parse().map_err(|e| format!("{}: {}", flag, e))
+}
+
+pub fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+    let mut opts = Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--no-donut-stroke" => opts.donut_stroke = false,
+            "--min-slice-angle" => {
+                let degrees: f64 = parse_value(&mut args, "--min-slice-angle")?;
+                if !(degrees > 0.0 && degrees <= 30.0) { return Err("--min-slice-angle must be between 0 and 30 degrees".to_string()); }
+                opts.min_slice_angle = Some(degrees);
+            }
+            "--no-legend" => opts.legend = false,
+            "--style" => opts.style = next_value(&mut args, "--style")?.parse()?,
+            "--layout" => opts.layout = next_value(&mut args, "--layout")?.parse()?,
+            "--granularity" => opts.granularity = next_value(&mut args, "--granularity")?.parse()?,
+            "--week-start" => opts.week_start = next_value(&mut args, "--week-start")?.parse()?,
+            "--max-canvas-bars" => {
+                let max: usize = parse_value(&mut args, "--max-canvas-bars")?;
+                if max == 0 { return Err("--max-canvas-bars must be at least 1".to_string()); }
+                opts.max_canvas_bars = max;
+            }
+            "--legend-cols" => {
+                let cols: usize = parse_value(&mut args, "--legend-cols")?;
+                if cols == 0 { return Err("--legend-cols must be at least 1".to_string()); }
+                opts.legend_cols = Some(cols);
+            }
+            "--other-below" => {
+                let percent: f64 = parse_value(&mut args, "--other-below")?;
+                if !(0.0..=50.0).contains(&percent) { return Err("--other-below must be between 0 and 50 percent".to_string()); }
+                opts.other_below = percent;
+            }
+            "--legend-sizes" => opts.legend_sizes = true,
+            "--font-family" => opts.font_family = next_value(&mut args, "--font-family")?,
+            "--clamp-count" => {
+                let cap: i32 = parse_value(&mut args, "--clamp-count")?;
+                if cap <= 0 { return Err("--clamp-count must be positive".to_string()); }
+                opts.clamp_count = Some(cap);
+            }
+            "--goal" => {
+                let goal: i32 = parse_value(&mut args, "--goal")?;
+                if goal <= 0 { return Err("--goal must be positive".to_string()); }
+                opts.goal = Some(goal);
+            }
+            "--goal-ring" => opts.goal_ring = true,
+            "--highlight-recent" => {
+                let n: usize = parse_value(&mut args, "--highlight-recent")?;
+                if n == 0 { return Err("--highlight-recent must be at least 1".to_string()); }
+                opts.highlight_recent = Some(n);
+            }
+            "--dim-older" => opts.dim_older = true,
+            "--line-scale" => opts.line_scale = next_value(&mut args, "--line-scale")?.parse()?,
+            "--min-height" => {
+                let h: f64 = parse_value(&mut args, "--min-height")?;
+                if !(h.is_finite() && h >= 0.0) { return Err("--min-height must be a non-negative number".to_string()); }
+                opts.min_height = h;
+            }
+            "--height-mode" => opts.height_mode = next_value(&mut args, "--height-mode")?.parse()?,
+            "--height-curve" => opts.height_curve = next_value(&mut args, "--height-curve")?.parse()?,
+            "--bar-scale" => {
+                let scale: f64 = parse_value(&mut args, "--bar-scale")?;
+                if !(scale.is_finite() && scale > 0.0) { return Err("--bar-scale must be a positive number".to_string()); }
+                opts.bar_scale = scale;
+            }
+            "--bar-max" => {
+                let value = next_value(&mut args, "--bar-max")?;
+                opts.bar_max = match value.as_str() {
+                    "none" => None,
+                    _ => match value.parse::<f64>() {
+                        Ok(max) if max.is_finite() && max > 0.0 => Some(max),
+                        _ => return Err(format!("--bar-max: expected a positive number of pixels or none, got {}", value)),
+                    },
+                };
+            }
+            "--cell-radius" => {
+                let r: f64 = parse_value(&mut args, "--cell-radius")?;
+                if !(0.0..=0.5).contains(&r) { return Err("--cell-radius must be between 0 and 0.5 (a fraction of the cell size)".to_string()); }
+                opts.cell_radius = r;
+            }
+            "--radar-opacity" => {
+                let o: f64 = parse_value(&mut args, "--radar-opacity")?;
+                if !(0.0..=1.0).contains(&o) { return Err("--radar-opacity must be between 0 and 1".to_string()); }
+                opts.radar_opacity = o;
+            }
+            "--panel-gap" => {
+                let gap: f64 = parse_value(&mut args, "--panel-gap")?;
+                if !(0.0..=80.0).contains(&gap) { return Err("--panel-gap must be between 0 and 80".to_string()); }
+                opts.panel_gap = gap;
+            }
+            "--year-separators" => opts.year_separators = true,
+            "--week-ticks" => opts.week_ticks = parse_value(&mut args, "--week-ticks")?,
+            "--trim-empty-edges" => opts.trim_empty_edges = true,
+            "--top-days" => {
+                let n: usize = parse_value(&mut args, "--top-days")?;
+                if n > MAX_TOP_DAYS { return Err(format!("--top-days can list at most {} days", MAX_TOP_DAYS)); }
+                opts.top_days = n;
+            }
+            "--annotate" => {
+                let value = next_value(&mut args, "--annotate")?;
+                match value.parse() {
+                    Ok(annotation) => opts.annotations.push(annotation),
+                    Err(e) => eprintln!("warning: skipping --annotate {}: {}", value, e),
+                }
+            }
+            "--mini" => opts.mini = true,
+            "--orientation" => {
+                let orientation: Orientation = next_value(&mut args, "--orientation")?.parse()?;
+                opts.projection.mirrored = orientation == Orientation::Mirrored;
+            }
+            "--only" => opts.only = Some(next_value(&mut args, "--only")?.parse()?),
+            "--compact-footer" => opts.compact_footer = true,
+            "--cvd-safe" => opts.cvd_safe = true,
+            "--no-footer" => opts.panels.footer = false,
+            "--panels" => {
+                for name in next_value(&mut args, "--panels")?.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                    let (show, panel) = match name.strip_prefix("no-") {
+                        Some(panel) => (false, panel),
+                        None => (true, name),
+                    };
+                    *match panel {
+                        "donut" => &mut opts.panels.donut,
+                        "radar" => &mut opts.panels.radar,
+                        "footer" => &mut opts.panels.footer,
+                        "repo-langs" => &mut opts.panels.repo_langs,
+                        other => return Err(format!("unknown panel: {} (expected donut|radar|footer|repo-langs, optionally prefixed with no-)", other)),
+                    } = show;
+                }
+            }
+            "--bg-gradient" => {
+                let stops: Vec<String> = next_value(&mut args, "--bg-gradient")?.split(',').map(|s| s.trim().to_string()).collect();
+                if stops.len() < 2 || !stops.iter().all(|s| is_hex_color(s)) {
+                    return Err("--bg-gradient takes two or more comma-separated hex colors".to_string());
+                }
+                opts.background = Background::Gradient(stops);
+            }
+            "--bg-image" => {
+                let url = next_value(&mut args, "--bg-image")?;
+                if url.trim().is_empty() || url.contains(['"', '<', '>']) {
+                    return Err("--bg-image needs a URL without quotes or angle brackets".to_string());
+                }
+                opts.background = Background::Image(url);
+            }
+            "--axis-labels" => opts.axis_labels = next_value(&mut args, "--axis-labels")?.parse()?,
+            "--intensity-levels" => {
+                let levels: usize = parse_value(&mut args, "--intensity-levels")?;
+                if !(3..=9).contains(&levels) { return Err("--intensity-levels must be between 3 and 9".to_string()); }
+                opts.intensity_levels = levels;
+            }
+            "--lang-weight" => opts.lang_weight = next_value(&mut args, "--lang-weight")?.parse()?,
+            "--color-by" => opts.color_by = Some(next_value(&mut args, "--color-by")?.parse()?),
+            "--color-mode" => opts.color_mode = next_value(&mut args, "--color-mode")?.parse()?,
+            "--gradient-stops" => {
+                let stops: Vec<String> = next_value(&mut args, "--gradient-stops")?.split(',').map(|s| s.trim().to_string()).collect();
+                if !(2..=3).contains(&stops.len()) || !stops.iter().all(|s| is_hex_color(s)) {
+                    return Err("--gradient-stops takes two or three comma-separated hex colors".to_string());
+                }
+                opts.gradient_stops = stops;
+            }
+            "--radar-benchmark" => opts.radar_benchmark = Some(DEFAULT_RADAR_BENCHMARK),
+            "--radar-benchmark-values" => {
+                let values: Vec<i32> = next_value(&mut args, "--radar-benchmark-values")?.split(',').map(|v| v.trim().parse().ok().filter(|&v: &i32| v > 0)).collect::<Option<_>>().unwrap_or_default();
+                let refs: [i32; 5] = values.try_into().map_err(|_| "--radar-benchmark-values takes five positive integers: commits,issues,prs,reviews,repos".to_string())?;
+                opts.radar_benchmark = Some(refs);
+            }
+            "--shadow" => opts.shadow = true,
+            "--bevel" => opts.bevel = true,
+            "--theme" => opts.theme = next_value(&mut args, "--theme")?.parse()?,
+            "--theme-file" => opts.theme = Theme::from_file(std::path::Path::new(&next_value(&mut args, "--theme-file")?))?,
+            "--emit-data-uri" => opts.emit_data_uri = true,
+            "--explain" => opts.explain = true,
+            "--embed-data" => opts.embed_data = true,
+            "--show-name" => opts.show_name = true,
+            "--show-avatar" => opts.show_avatar = true,
+            "--all-time" => opts.all_time = true,
+            "--from" => opts.from = Some(parse_value(&mut args, "--from")?),
+            "--to" => opts.to = Some(parse_value(&mut args, "--to")?),
+            "--year" => {
+                let year: i32 = parse_value(&mut args, "--year")?;
+                let (Some(from), Some(to)) = (Date::new(year, 1, 1), Date::new(year, 12, 31)) else {
+                    return Err(format!("--year: invalid year {}", year));
+                };
+                (opts.from, opts.to) = (Some(from), Some(to));
+            }
+            "--source" => opts.source = next_value(&mut args, "--source")?.parse()?,
+            "--timezone" => opts.timezone = next_value(&mut args, "--timezone")?.parse()?,
+            "--verbose" | "-v" => opts.verbose = true,
+            "--interactive" => opts.interactive = true,
+            "--locale" => opts.locale = next_value(&mut args, "--locale")?.parse()?,
+            other => return Err(format!("unknown argument: {}", other)),
+        }
+    }
+    match (opts.from, opts.to) {
+        (None, Some(_)) => return Err("--to needs --from".to_string()),
+        (Some(from), Some(to)) if from > to => return Err(format!("--from {} is after --to {}", from, to)),
+        (Some(_), _) if opts.all_time => return Err("--all-time can't be combined with --from or --year".to_string()),
+        _ => {}
+    }
+    if opts.goal_ring && opts.goal.is_none() {
+        return Err("--goal-ring requires --goal".to_string());
+    }
+    Ok(opts)
+}
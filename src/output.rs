@@ -0,0 +1,227 @@
+//! Serializing a rendered card into its output forms.
+
+use crate::github::{iter_days, week_totals, User, Week};
+use crate::options::Options;
+use crate::render::{draw_profile, render_profile, CardPart};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use svg::Document;
+
+/// A failed write of an output file, with the path and a hint at the likely
+/// cause for the common cases (e.g. a read-only checkout in CI).
+#[derive(Debug)]
+pub struct SaveError {
+    pub path: PathBuf,
+    pub source: io::Error,
+}
+
+impl SaveError {
+    fn hint(&self) -> Option<&'static str> {
+        match self.source.kind() {
+            io::ErrorKind::PermissionDenied => Some("check that the directory is writable"),
+            io::ErrorKind::ReadOnlyFilesystem => Some("the filesystem is read-only; write somewhere else"),
+            io::ErrorKind::NotFound => Some("the parent directory doesn't exist"),
+            io::ErrorKind::NotADirectory => Some("part of the path is a file, not a directory"),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "could not write {}: {}", self.path.display(), self.source)?;
+        match self.hint() {
+            Some(hint) => write!(f, " ({})", hint),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Error for SaveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Writes an output file (card, CSV, saved data), reporting failures as a
+/// `SaveError` that names the path.
+pub fn save_file(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> Result<(), SaveError> {
+    let path = path.as_ref();
+    std::fs::write(path, contents).map_err(|source| SaveError { path: path.to_path_buf(), source })
+}
+
+/// `save_file` for a rendered document, gzip-compressed when the path ends
+/// in `.svgz`.
+pub fn save_svg(path: impl AsRef<Path>, doc: &Document) -> Result<(), SaveError> {
+    let path = path.as_ref();
+    if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("svgz")) {
+        save_file(path, crate::gzip::gzip(doc.to_string().as_bytes()))
+    } else {
+        save_file(path, doc.to_string())
+    }
+}
+
+/// `save_svg` for a profile card, written panel by panel as it's drawn
+/// instead of rendered to a `Document` first. A `.svgz` is compressed in one
+/// go, so it's rendered whole and saved with `save_svg`.
+pub fn save_card_streamed(path: impl AsRef<Path>, user: &User, opts: &Options) -> Result<(), SaveError> {
+    let path = path.as_ref();
+    if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("svgz")) {
+        return save_svg(path, &render_profile(user, opts));
+    }
+    let fail = |source| SaveError { path: path.to_path_buf(), source };
+    let mut file = Some(BufWriter::new(File::create(path).map_err(fail)?));
+    let mut stream = None;
+    let mut result = Ok(());
+    draw_profile(user, opts, &mut |part| {
+        if result.is_err() {
+            return;
+        }
+        result = match part {
+            CardPart::Root(root) => SvgStream::begin(file.take().expect("one root per card"), &root).map(|s| stream = Some(s)),
+            CardPart::Node(node) => stream.as_mut().expect("the root comes first").push(&node),
+        };
+    });
+    result.and_then(|()| stream.expect("every card has a root").finish().map(drop)).map_err(fail)
+}
+
+/// Writes an SVG one top-level node at a time instead of from a finished
+/// `Document`, flushing after each, so a composite of many panels never has
+/// to sit in memory whole and an interrupted run leaves everything written so
+/// far on disk. The viewBox goes in the opening tag, so callers must know the
+/// canvas size up front. The output matches what a `Document` with the same
+/// attributes and children would serialize to.
+///
+/// `save_card_streamed` drives one from `render::draw_profile`.
+pub struct SvgStream<W: Write> {
+    out: W,
+}
+
+impl<W: Write> SvgStream<W> {
+    /// Writes the opening tag of `root`, and any children it already has.
+    pub fn begin(mut out: W, root: &Document) -> io::Result<Self> {
+        let whole = root.to_string();
+        match whole.strip_suffix("/>") {
+            Some(tag) => writeln!(out, "{}>", tag)?,
+            None => writeln!(out, "{}", whole.strip_suffix("\n</svg>").unwrap_or(&whole))?,
+        }
+        Ok(SvgStream { out })
+    }
+
+    pub fn push(&mut self, node: &impl fmt::Display) -> io::Result<()> {
+        writeln!(self.out, "{}", node)?;
+        self.out.flush()
+    }
+
+    /// Closes the root element and hands back the writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        write!(self.out, "</svg>")?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// Encodes the document as `data:image/svg+xml;base64,...`, suitable for an
+/// HTML `src` attribute or a CSS `url()` without a separate file.
+pub fn to_data_uri(doc: &Document) -> String {
+    format!("data:image/svg+xml;base64,{}", STANDARD.encode(doc.to_string()))
+}
+
+/// The daily series as `date,count` rows in chronological order, with a
+/// header. Days without a date can't be placed and are left out.
+pub fn daily_csv(weeks: &[Week]) -> String {
+    let mut days: Vec<_> = iter_days(weeks).filter_map(|(_, d)| Some((d.date?, d.contribution_count))).collect();
+    days.sort_by_key(|(date, _)| *date);
+    let mut csv = String::from("date,count\n");
+    for (date, count) in days {
+        csv.push_str(&format!("{},{}\n", date, count));
+    }
+    csv
+}
+
+const SPARK_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Per-week totals as one line of block characters, scaled to the busiest
+/// week, for shell prompts and other plain-text embeds. Empty weeks get the
+/// lowest block; any activity shows at least one step above it.
+pub fn sparkline(weeks: &[Week]) -> String {
+    let totals = week_totals(weeks);
+    let max = totals.iter().copied().max().unwrap_or(0);
+    let top = SPARK_BLOCKS.len() - 1;
+    totals
+        .iter()
+        .map(|&t| if t <= 0 { SPARK_BLOCKS[0] } else { SPARK_BLOCKS[((t as usize * top).div_ceil(max as usize)).clamp(1, top)] })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use svg::node::element::Rectangle;
+
+    #[test]
+    fn streamed_output_matches_the_document() {
+        let a = Rectangle::new().set("width", 4);
+        let b = Rectangle::new().set("height", 2);
+        let doc = Document::new().set("viewBox", (0.0, 0.0, 10.0, 5.0)).set("style", "background:#fff;").add(a.clone()).add(b.clone());
+
+        let mut stream = SvgStream::begin(Vec::new(), &Document::new().set("viewBox", (0.0, 0.0, 10.0, 5.0)).set("style", "background:#fff;")).unwrap();
+        stream.push(&a).unwrap();
+        stream.push(&b).unwrap();
+        assert_eq!(String::from_utf8(stream.finish().unwrap()).unwrap(), doc.to_string());
+    }
+
+    #[test]
+    fn streamed_card_matches_the_rendered_one() {
+        let path = std::env::temp_dir().join(format!("heatmap-stream-test-{}.svg", std::process::id()));
+        let user = crate::demo::demo_user();
+        for opts in [Options::default(), Options { interactive: true, shadow: true, ..Options::default() }, Options { mini: true, ..Options::default() }] {
+            save_card_streamed(&path, &user, &opts).unwrap();
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), render_profile(&user, &opts).to_string());
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn sparkline_scales_weeks_to_blocks() {
+        let weeks: Vec<Week> = serde_json::from_value(serde_json::json!([
+            { "contributionDays": [{ "contributionCount": 0 }] },
+            { "contributionDays": [{ "contributionCount": 1 }] },
+            { "contributionDays": [{ "contributionCount": 7 }, { "contributionCount": 7 }] },
+            { "contributionDays": [{ "contributionCount": 7 }] }
+        ]))
+        .unwrap();
+        assert_eq!(sparkline(&weeks), "▁▂█▅");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn failed_save_names_the_path_and_cause() {
+        // A regular file can't hold children, even for root
+        let file = std::env::temp_dir().join(format!("heatmap-save-test-{}", std::process::id()));
+        std::fs::write(&file, "").unwrap();
+        let target = file.join("card.svg");
+        let err = save_svg(&target, &Document::new()).unwrap_err();
+        std::fs::remove_file(&file).unwrap();
+
+        let message = err.to_string();
+        assert!(message.contains(&target.display().to_string()), "{}", message);
+        assert!(message.contains("not a directory"), "{}", message);
+    }
+
+    #[test]
+    fn data_uri_decodes_back_to_the_svg() {
+        let doc = Document::new().set("viewBox", (0, 0, 10, 10)).add(Rectangle::new().set("width", 10));
+        let uri = to_data_uri(&doc);
+        let payload = uri.strip_prefix("data:image/svg+xml;base64,").expect("data URI prefix");
+
+        let decoded = String::from_utf8(STANDARD.decode(payload).unwrap()).unwrap();
+        assert_eq!(decoded, doc.to_string());
+        assert!(decoded.starts_with("<svg") && decoded.ends_with("</svg>"));
+    }
+}
@@ -0,0 +1,48 @@
+//! Named output presets for `--presets`: the same data rendered at fixed
+//! platform sizes, each with its own set of panels.
+
+use crate::options::{Options, Panels};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Preset {
+    pub name: &'static str,
+    pub width: u32,
+    pub height: u32,
+    pub panels: Panels,
+}
+
+/// The registry; add an entry here to make a new preset available.
+pub const PRESETS: &[Preset] = &[
+    // Summary card image: the heatmap and radar read at a glance, the donut doesn't
+    Preset { name: "twitter", width: 1200, height: 675, panels: Panels { donut: false, radar: true, footer: true, repo_langs: false } },
+    // Link previews are cropped and tiny; keep just the heatmap and totals
+    Preset { name: "og", width: 1200, height: 630, panels: Panels { donut: false, radar: false, footer: true, repo_langs: false } },
+    Preset { name: "readme", width: 1400, height: 1000, panels: Panels { donut: true, radar: true, footer: true, repo_langs: false } },
+];
+
+pub fn find_preset(name: &str) -> Option<&'static Preset> {
+    PRESETS.iter().find(|p| p.name == name)
+}
+
+impl Preset {
+    /// `base` with this preset's size and panels.
+    pub fn apply(&self, base: &Options) -> Options {
+        Options { size: Some((self.width, self.height)), panels: self.panels, ..base.clone() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::demo::demo_user;
+    use crate::render::render_profile;
+
+    #[test]
+    fn presets_set_size_and_hide_panels() {
+        let og = find_preset("og").unwrap().apply(&Options::default());
+        let svg = render_profile(&demo_user(), &og).to_string();
+        assert!(svg.contains("width=\"1200\"") && svg.contains("height=\"630\""));
+        assert!(!svg.contains("PullReq"));
+        assert!(find_preset("myspace").is_none());
+    }
+}
@@ -0,0 +1,2292 @@
+//! SVG drawing for the heatmap, donut and radar panels.
+
+use crate::annotate::draw_annotations;
+use crate::date::Date;
+use crate::defs::{bar_filter, build_defs, AVATAR_CLIP_ID, BACKGROUND_GRADIENT_ID};
+use crate::diff::is_new_activity;
+use crate::escape::{escape_attr, escape_text};
+use crate::github::{aggregate_languages, aggregate_primary_languages, empty_edge_weeks, language_breakdown, repo_languages, week_totals, Day, LanguageStat, RepoLanguage, User, Week};
+use crate::options::{AxisLabels, Background, ColorMode, Granularity, HeatmapLayout, HeightMode, LangWeight, LineScale, OnlyPanel, Options, Source, Style, WeekStart};
+use crate::rings::draw_year_rings;
+use crate::stats::{data_island, top_days};
+use crate::theme::contrast_ratio;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use svg::node::element::{Circle, Group, Image, Path, Polygon, Rectangle, Script, Text as SvgText, Title};
+use svg::node::{Node, Text as TextNode};
+use svg::Document;
+
+// Larger canvas to prevent crowding
+pub const VIEW_WIDTH: f64 = 1400.0;
+pub const VIEW_HEIGHT: f64 = 1000.0;
+// Top-face color for days with activity since the --diff-against snapshot
+const NEW_ACTIVITY: &str = "#fb8532";
+// Days at or above --goal, and the goal plane
+const GOAL_MET: &str = "#0366d6";
+
+/// Isometric projection from grid space (x = week, y = weekday, z = bar height
+/// in pixels) to screen space:
+///
+///   sx = origin_x + (x - y) * cos(angle) * scale
+///   sy = origin_y + (x + y) * sin(angle) * scale - z
+///
+/// Height is applied unscaled so bar heights stay in screen pixels. With
+/// `mirrored` (`--orientation mirrored`) the result is reflected about the
+/// canvas's vertical center line, so everything placed through the projection
+/// (bars, floor, ticks, labels) flips together while depth order is unchanged.
+#[derive(Clone, Copy, Debug)]
+pub struct Projection {
+    pub origin_x: f64,
+    pub origin_y: f64,
+    pub angle_deg: f64,
+    pub scale: f64,
+    pub mirrored: bool,
+}
+
+impl Default for Projection {
+    fn default() -> Self {
+        // Scale of 20.0 gives the much longer/wider "extended" look
+        Projection { origin_x: 400.0, origin_y: 300.0, angle_deg: 30.0, scale: 20.0, mirrored: false }
+    }
+}
+
+impl Projection {
+    pub fn project(&self, x: f64, y: f64, z: f64) -> (f64, f64) {
+        let angle = self.angle_deg.to_radians();
+        let sx = self.origin_x + (x - y) * angle.cos() * self.scale;
+        let sy = self.origin_y + (x + y) * angle.sin() * self.scale - z;
+        (if self.mirrored { VIEW_WIDTH - sx } else { sx }, sy)
+    }
+}
+
+// Shading for bar faces: scales HSL lightness by `amount` so hue and
+// saturation hold and a face reads as the same color in shadow. Accepts hex
+// and `rgb()`/`rgba()` (keeping the alpha); anything else is returned as is.
+fn darken(color: &str, amount: f64) -> String {
+    let Some((rgb, alpha)) = parse_rgb(color).map(|rgb| (rgb, None)).or_else(|| parse_rgb_function(color)) else {
+        return color.to_string();
+    };
+    let (h, s, l) = rgb_to_hsl(rgb);
+    let (r, g, b) = hsl_to_rgb(h, s, (l * amount).clamp(0.0, 1.0));
+    match alpha {
+        Some(a) => format!("rgba({}, {}, {}, {})", r, g, b, a),
+        None => format!("#{:02x}{:02x}{:02x}", r, g, b),
+    }
+}
+
+// `rgb(r, g, b)` or `rgba(r, g, b, a)` with 0-255 channels and alpha in [0, 1].
+fn parse_rgb_function(color: &str) -> Option<((u8, u8, u8), Option<f64>)> {
+    let color = color.trim();
+    let (args, has_alpha) = match color.strip_prefix("rgba(") {
+        Some(rest) => (rest, true),
+        None => (color.strip_prefix("rgb(")?, false),
+    };
+    let parts: Vec<&str> = args.strip_suffix(')')?.split(',').map(str::trim).collect();
+    if parts.len() != if has_alpha { 4 } else { 3 } {
+        return None;
+    }
+    let channel = |i: usize| parts[i].parse::<u8>().ok();
+    let alpha = match parts.get(3) {
+        Some(a) => Some(a.parse::<f64>().ok().filter(|a| (0.0..=1.0).contains(a))?),
+        None => None,
+    };
+    Some(((channel(0)?, channel(1)?, channel(2)?), alpha))
+}
+
+fn parse_rgb(hex: &str) -> Option<(u8, u8, u8)> {
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    match hex.len() {
+        6 => Some((channel(hex.get(0..2)?)?, channel(hex.get(2..4)?)?, channel(hex.get(4..6)?)?)),
+        3 => {
+            let short = |i: usize| channel(hex.get(i..i + 1)?).map(|v| v * 17);
+            Some((short(0)?, short(1)?, short(2)?))
+        }
+        _ => None,
+    }
+}
+
+// Hue in [0, 6), saturation and lightness in [0, 1].
+fn rgb_to_hsl((r, g, b): (u8, u8, u8)) -> (f64, f64, f64) {
+    let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+    let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d == 0.0 {
+        return (0.0, 0.0, l);
+    }
+    let s = d / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r { ((g - b) / d).rem_euclid(6.0) } else if max == g { (b - r) / d + 2.0 } else { (r - g) / d + 4.0 };
+    (h, s, l)
+}
+
+fn hsl_to_rgb(h: f64, s: f64, l: f64) -> (u8, u8, u8) {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let x = c * (1.0 - (h.rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = l - c / 2.0;
+    let to_u8 = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+// Nonzero days only; empty ones take the theme's empty cell in `cell_color`.
+pub(crate) fn get_seasonal_color(week_idx: usize) -> String {
+    match week_idx {
+        0..=12  => "#c6e48b".to_string(), // Q1
+        13..=25 => "#f4e04d".to_string(), // Q2
+        26..=38 => "#a3a3a3".to_string(), // Q3
+        _       => "#d1a3d1".to_string(), // Q4
+    }
+}
+
+/// Palette for `--cvd-safe`. The qualitative set is Okabe and Ito's, chosen
+/// to stay distinct under protanopia, deuteranopia and tritanopia; the
+/// intensity ramp samples viridis, whose lightness rises monotonically so
+/// levels also read in grayscale (achromatopsia). The default palettes lean
+/// on red/green contrast that protan and deutan viewers largely lose.
+pub(crate) const CVD_QUALITATIVE: [&str; 8] = ["#e69f00", "#56b4e9", "#009e73", "#f0e442", "#0072b2", "#d55e00", "#cc79a7", "#999999"];
+const CVD_INTENSITY_RAMP: [&str; 4] = ["#3b528b", "#21908d", "#5dc963", "#fde725"];
+
+// Seasonal buckets in the --cvd-safe palette: one Okabe-Ito hue per quarter.
+fn cvd_seasonal_color(week_idx: usize) -> String {
+    CVD_QUALITATIVE[(week_idx / 13).min(3)].to_string()
+}
+
+// Linear RGB blend between two colors, t in [0, 1].
+fn lerp_color(a: (u8, u8, u8), b: (u8, u8, u8), t: f64) -> (u8, u8, u8) {
+    let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    (mix(a.0, b.0), mix(a.1, b.1), mix(a.2, b.2))
+}
+
+/// Color at `t` (clamped to [0, 1]) along evenly spaced gradient stops.
+pub(crate) fn gradient_color(stops: &[String], t: f64) -> String {
+    let rgb: Vec<(u8, u8, u8)> = stops.iter().map(|s| parse_rgb(s).unwrap_or((200, 200, 200))).collect();
+    let (r, g, b) = match rgb.len() {
+        0 => (200, 200, 200),
+        1 => rgb[0],
+        n => {
+            let pos = t.clamp(0.0, 1.0) * (n - 1) as f64;
+            let i = (pos.floor() as usize).min(n - 2);
+            lerp_color(rgb[i], rgb[i + 1], pos - i as f64)
+        }
+    };
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+// Largest count that drives a bar, after --clamp-count.
+pub(crate) fn max_drawn_count(weeks: &[Week], opts: &Options) -> i32 {
+    let max = weeks.iter().flat_map(|w| &w.contribution_days).map(|d| d.contribution_count).max().unwrap_or(0);
+    opts.clamp_count.map_or(max, |cap| max.min(cap))
+}
+
+/// Default for `--intensity-levels`: GitHub's empty level plus four shades.
+pub const DEFAULT_INTENSITY_LEVELS: usize = 5;
+
+// GitHub's nonzero shades, interpolated to however many levels are asked for
+const INTENSITY_RAMP: [&str; 4] = ["#9be9a8", "#40c463", "#30a14e", "#216e39"];
+
+// Nonzero end of the default --gradient-stops; count 0 starts from the
+// theme's empty cell so the ramp matches the rest of the card.
+const DEFAULT_GRADIENT: [&str; 2] = ["#9be9a8", "#216e39"];
+
+// --gradient-stops as given, else the default ramp for the theme.
+fn gradient_stops(opts: &Options) -> Cow<'_, [String]> {
+    if !opts.gradient_stops.is_empty() {
+        return Cow::Borrowed(&opts.gradient_stops);
+    }
+    Cow::Owned([opts.theme.empty_cell.as_str()].into_iter().chain(DEFAULT_GRADIENT).map(String::from).collect())
+}
+
+/// What a calendar's fills are computed against: the largest drawn count
+/// for the gradient, for intensity levels the lower bound of every level
+/// above the first nonzero one, and the first day `--highlight-recent`
+/// covers.
+pub(crate) struct ColorScale {
+    max: i32,
+    thresholds: Vec<i32>,
+    recent_from: Option<Date>,
+}
+
+impl ColorScale {
+    pub(crate) fn new(weeks: &[Week], opts: &Options) -> Self {
+        let thresholds = match opts.color_mode {
+            ColorMode::Intensity => intensity_thresholds(weeks, opts.intensity_levels),
+            _ => Vec::new(),
+        };
+        ColorScale { max: max_drawn_count(weeks, opts), thresholds, recent_from: recent_from(weeks, opts) }
+    }
+
+    /// Whether `day` falls in the `--highlight-recent` window; always false
+    /// without one.
+    pub(crate) fn is_recent(&self, day: &Day) -> bool {
+        day.date.zip(self.recent_from).is_some_and(|(date, from)| date >= from)
+    }
+
+    // --dim-older applies only alongside a window to contrast it with
+    fn dims(&self, day: &Day, opts: &Options) -> bool {
+        opts.dim_older && self.recent_from.is_some() && !self.is_recent(day)
+    }
+}
+
+// The last N dated days of the calendar, ending on its final day rather than
+// today so saved snapshots highlight the same days they did when fetched.
+fn recent_from(weeks: &[Week], opts: &Options) -> Option<Date> {
+    let n = opts.highlight_recent.filter(|_| opts.granularity == Granularity::Day)?;
+    let last = weeks.iter().flat_map(|w| &w.contribution_days).filter_map(|d| d.date).max()?;
+    Some(last.add_days(1 - n as i64))
+}
+
+// Nearest-rank quantiles splitting the nonzero counts into `levels - 1`
+// equally populated buckets.
+fn intensity_thresholds(weeks: &[Week], levels: usize) -> Vec<i32> {
+    let mut counts: Vec<i32> = weeks.iter().flat_map(|w| &w.contribution_days).map(|d| d.contribution_count).filter(|&c| c > 0).collect();
+    counts.sort_unstable();
+    let buckets = levels.saturating_sub(1).max(1);
+    if counts.is_empty() {
+        return Vec::new();
+    }
+    (1..buckets).map(|k| counts[(k * counts.len() / buckets).min(counts.len() - 1)]).collect()
+}
+
+// Level 0 for empty days, else 1 plus the thresholds the count reaches.
+fn intensity_level(count: i32, thresholds: &[i32]) -> usize {
+    if count <= 0 { 0 } else { 1 + thresholds.iter().filter(|&&t| count >= t).count() }
+}
+
+// Levels from 1; level 0 is the theme's empty cell, handled by `cell_color`.
+fn intensity_color(level: usize, levels: usize, cvd_safe: bool) -> String {
+    let ramp: Vec<String> = if cvd_safe { CVD_INTENSITY_RAMP } else { INTENSITY_RAMP }.iter().map(|c| c.to_string()).collect();
+    let shades = levels.saturating_sub(1).max(2);
+    gradient_color(&ramp, level.saturating_sub(1) as f64 / (shades - 1) as f64)
+}
+
+/// Fill for a day: seasonal buckets by default, with `--color-mode
+/// gradient` the count's position between 0 and the max along the stops,
+/// with `--color-mode intensity` its quantile level.
+pub(crate) fn cell_color(week_idx: usize, count: i32, scale: &ColorScale, opts: &Options) -> String {
+    // Gradients start from their own first stop
+    if count <= 0 && opts.color_mode != ColorMode::Gradient {
+        return opts.theme.empty_cell.clone();
+    }
+    match opts.color_mode {
+        ColorMode::Seasonal if opts.cvd_safe => cvd_seasonal_color(week_idx),
+        ColorMode::Seasonal => get_seasonal_color(week_idx),
+        ColorMode::Gradient => gradient_color(&gradient_stops(opts), if scale.max > 0 { count as f64 / scale.max as f64 } else { 0.0 }),
+        ColorMode::Intensity => intensity_color(intensity_level(count, &scale.thresholds), opts.intensity_levels, opts.cvd_safe),
+    }
+}
+
+// With --color-by, a bar's fill follows the secondary series instead of its
+// height: the day's share of the busiest day along the gradient stops.
+fn secondary_color(day: &Day, opts: &Options) -> Option<String> {
+    let series = opts.color_series.as_ref()?;
+    let max = series.values().copied().max().unwrap_or(0);
+    let count = day.date.and_then(|d| series.get(&d).copied()).unwrap_or(0);
+    Some(gradient_color(&gradient_stops(opts), if max > 0 { count as f64 / max as f64 } else { 0.0 }))
+}
+
+// How far --dim-older blends older days toward the background
+const DIM_OLDER: f64 = 0.45;
+// Outline width around --highlight-recent days
+const RECENT_STROKE: f64 = 1.5;
+
+// Fill for a drawn day, by precedence: new since the --diff-against
+// baseline, met the --goal, the --color-by series, then the color mode;
+// then washed out if --dim-older applies.
+fn day_fill(week_idx: usize, day: &Day, scale: &ColorScale, opts: &Options) -> String {
+    let color = base_fill(week_idx, day, scale, opts);
+    if scale.dims(day, opts) {
+        // Washed toward the background rather than made translucent, so
+        // bars behind don't show through
+        let (fg, bg) = (parse_rgb(&color).unwrap_or((200, 200, 200)), parse_rgb(&opts.theme.background).unwrap_or((255, 255, 255)));
+        let (r, g, b) = lerp_color(fg, bg, DIM_OLDER);
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    } else {
+        color
+    }
+}
+
+fn base_fill(week_idx: usize, day: &Day, scale: &ColorScale, opts: &Options) -> String {
+    if opts.baseline.as_ref().is_some_and(|b| is_new_activity(b, day)) {
+        NEW_ACTIVITY.to_string()
+    } else if met_goal(day.contribution_count, opts) {
+        GOAL_MET.to_string()
+    } else {
+        secondary_color(day, opts).unwrap_or_else(|| cell_color(week_idx, day.contribution_count, scale, opts))
+    }
+}
+
+// The goal is per day, so weekly totals are never compared against it.
+fn met_goal(count: i32, opts: &Options) -> bool {
+    opts.granularity == Granularity::Day && opts.goal.is_some_and(|goal| count >= goal)
+}
+
+// --goal reference plane: a translucent sheet across the whole grid at the
+// height of a day with exactly the goal, drawn over the bars so shorter ones
+// show beneath it and taller ones poke through the outline.
+fn draw_goal_plane(weeks: &[Week], goal: i32, opts: &Options) -> Group {
+    let proj = &opts.projection;
+    let (cols, rows) = (weeks.len() as f64, weeks.iter().map(|w| w.contribution_days.len()).max().unwrap_or(0) as f64);
+    let h = cell_height(goal, opts);
+    let corners = [proj.project(0.0, 0.0, h), proj.project(cols, 0.0, h), proj.project(cols, rows, h), proj.project(0.0, rows, h)];
+    let label = format!("Goal: {} a day", opts.locale.format_int(goal as i64));
+    Group::new().add(
+        Polygon::new()
+            .set("points", points(&corners))
+            .set("fill", GOAL_MET).set("fill-opacity", 0.15)
+            .set("stroke", GOAL_MET).set("stroke-opacity", 0.6).set("stroke-width", stroke_width(1.0, opts))
+            .set("pointer-events", "none")
+            .add(Title::new().add(TextNode::new(label))),
+    )
+}
+
+// Full bar height under --height-mode normalized
+const NORMALIZED_CEILING: f64 = 100.0;
+
+/// Default for `--bar-scale`: pixels per contribution on the linear curve.
+pub const DEFAULT_BAR_SCALE: f64 = 5.0;
+/// Default for `--bar-max`: tall enough for 50 contributions at the default
+/// scale while the back corner's bar stays on the canvas.
+pub const DEFAULT_BAR_MAX: f64 = 250.0;
+
+// Height in pixels for a day's count before --bar-max, capped by
+// --clamp-count so a single outlier day doesn't flatten everything else.
+fn unclamped_height(count: i32, opts: &Options) -> f64 {
+    let count = opts.clamp_count.map_or(count, |cap| count.min(cap));
+    let curve = |n: i32| opts.height_curve.apply(n as f64);
+    match opts.height_mode {
+        HeightMode::Linear => curve(count) * opts.bar_scale,
+        HeightMode::Normalized if opts.height_max > 0 => curve(count) / curve(opts.height_max) * NORMALIZED_CEILING,
+        HeightMode::Normalized => 0.0,
+    }
+}
+
+fn bar_height(count: i32, opts: &Options) -> f64 {
+    let h = unclamped_height(count, opts);
+    opts.bar_max.map_or(h, |max| h.min(max))
+}
+
+// Thin base tile for days without contributions
+const EMPTY_HEIGHT: f64 = 2.0;
+
+// Drawn bar height: empty days sit flat at EMPTY_HEIGHT while any nonzero day
+// gets at least --min-height, so a 1-count day never reads as empty.
+pub(crate) fn cell_height(count: i32, opts: &Options) -> f64 {
+    if count <= 0 { EMPTY_HEIGHT } else { bar_height(count, opts).max(opts.min_height) }
+}
+
+// --bevel geometry: the top face is inset by this fraction of a cell on each
+// side, and the chamfer drops at most BEVEL_MAX_DROP pixels.
+const BEVEL_INSET: f64 = 0.12;
+const BEVEL_MAX_DROP: f64 = 3.0;
+
+// Chamfer height for a bar; zero for bars too short to bevel (such as the
+// flat empty-day tiles), which keep a plain top.
+fn bevel_drop(h: f64) -> f64 {
+    if h < 2.0 * BEVEL_MAX_DROP { 0.0 } else { BEVEL_MAX_DROP }
+}
+
+/// Stroke width after `--line-scale`. `auto` undoes the shrink from `size`
+/// (a 1400-unit canvas shown 350 pixels wide quarters every line), so a
+/// 1-unit line still covers about a pixel however small the card is drawn
+/// or rasterized.
+pub(crate) fn stroke_width(width: f64, opts: &Options) -> f64 {
+    let factor = match opts.line_scale {
+        LineScale::Factor(f) => f,
+        LineScale::Auto => opts.size.map_or(1.0, |(w, _)| (VIEW_WIDTH / w as f64).max(1.0)),
+    };
+    width * factor
+}
+
+fn points(corners: &[(f64, f64)]) -> String {
+    corners.iter().map(|(x, y)| format!("{},{}", x, y)).collect::<Vec<_>>().join(" ")
+}
+
+fn is_clamped(count: i32, opts: &Options) -> bool {
+    opts.clamp_count.is_some_and(|cap| count > cap) || opts.bar_max.is_some_and(|max| unclamped_height(count, opts) > max)
+}
+
+// --- LAYOUT ---
+
+// Donut legend geometry, relative to the donut center. The legend's x offset
+// comes from PanelLayout.
+const LEGEND_Y: f64 = -80.0;
+const LEGEND_ROWS: usize = 8;
+const LEGEND_ROW_HEIGHT: f64 = 22.0;
+const LEGEND_COL_WIDTH: f64 = 140.0;
+const LEGEND_FONT_SIZE: f64 = 14.0;
+// Labels shrink to fit a --legend-cols column, but no further than this
+const LEGEND_MIN_FONT_SIZE: f64 = 9.0;
+// Swatch plus the gap before the label
+const LEGEND_LABEL_X: f64 = 18.0;
+// Space kept between a label and the next column
+const LEGEND_COL_GAP: f64 = 10.0;
+
+/// Default for `--other-below`, in percent.
+pub const DEFAULT_OTHER_BELOW: f64 = 1.0;
+const OTHER_COLOR: &str = "#8b949e";
+
+const DONUT_RADIUS: f64 = 90.0;
+const DONUT_INNER_RADIUS: f64 = 60.0;
+
+/// Default for `--panel-gap`; reproduces the original fixed placement.
+pub const DEFAULT_PANEL_GAP: f64 = 30.0;
+
+// Panel placement derived from --panel-gap. The gap separates the donut ring
+// from its legend, and the donut and radar panels move away from (or toward)
+// the heatmap by the same amount, so spacing changes evenly across the card.
+struct PanelLayout {
+    donut_center: (f64, f64),
+    legend_x: f64,
+    radar_center: (f64, f64),
+    // Panels swap sides with a mirrored heatmap; the legend then sits left
+    // of the donut, ending `legend_x` from its center.
+    mirrored: bool,
+}
+
+impl PanelLayout {
+    fn new(opts: &Options) -> Self {
+        let gap = opts.panel_gap;
+        let delta = gap - DEFAULT_PANEL_GAP;
+        let mirrored = opts.projection.mirrored;
+        let x = |x: f64| if mirrored { VIEW_WIDTH - x } else { x };
+        // Moved lower to avoid heatmap overlap; radar pushed far right and slightly up
+        PanelLayout { donut_center: (x(180.0), 820.0 + delta), legend_x: DONUT_RADIUS + gap, radar_center: (x(1150.0 + delta), 250.0), mirrored }
+    }
+
+    // Space beside the legend origin before hitting the canvas edge
+    fn legend_max_width(&self) -> f64 {
+        let room = if self.mirrored { self.donut_center.0 } else { VIEW_WIDTH - self.donut_center.0 };
+        room - self.legend_x - 40.0
+    }
+}
+
+struct LegendLayout {
+    x: f64,
+    rows: usize,
+    col_width: f64,
+    row_height: f64,
+    font_size: f64,
+}
+
+impl LegendLayout {
+    // Without a column count, fill fixed-height columns left to right, widened
+    // to the longest label. With one, spread the entries over that many columns
+    // and shrink spacing, then the labels, to fit.
+    fn new(labels: &[String], cols: Option<usize>, panels: &PanelLayout) -> Self {
+        let count = labels.len();
+        let x = panels.legend_x;
+        let widest = labels.iter().map(|l| l.chars().count() as f64 * LEGEND_FONT_SIZE * 0.6).fold(0.0, f64::max);
+        let wanted = LEGEND_COL_WIDTH.max(LEGEND_LABEL_X + widest + LEGEND_COL_GAP);
+        let mut layout = match cols {
+            None => LegendLayout { x, rows: LEGEND_ROWS, col_width: wanted, row_height: LEGEND_ROW_HEIGHT, font_size: LEGEND_FONT_SIZE },
+            Some(cols) => {
+                let rows = count.div_ceil(cols).max(1);
+                let max_height = LEGEND_ROWS as f64 * LEGEND_ROW_HEIGHT;
+                let col_width = (panels.legend_max_width() / cols as f64).min(wanted);
+                let room = col_width - LEGEND_LABEL_X - LEGEND_COL_GAP;
+                LegendLayout {
+                    x,
+                    rows,
+                    col_width,
+                    row_height: (max_height / rows as f64).clamp(14.0, LEGEND_ROW_HEIGHT),
+                    font_size: if widest > room { (LEGEND_FONT_SIZE * room / widest).max(LEGEND_MIN_FONT_SIZE) } else { LEGEND_FONT_SIZE },
+                }
+            }
+        };
+        if panels.mirrored {
+            layout.x = -x - layout.extent(count).0;
+        }
+        layout
+    }
+
+    // Width and height covered by `count` entries.
+    fn extent(&self, count: usize) -> (f64, f64) {
+        (count.div_ceil(self.rows) as f64 * self.col_width, count.min(self.rows) as f64 * self.row_height)
+    }
+
+    fn position(&self, i: usize) -> (f64, f64) {
+        let col = i / self.rows;
+        let row = i % self.rows;
+        (self.x + col as f64 * self.col_width, LEGEND_Y + row as f64 * self.row_height)
+    }
+}
+
+// --- DRAWING ---
+
+/// Path data for a ring segment centered on the origin between two angles
+/// (radians, clockwise from +x). A full turn is split in two, since an SVG arc
+/// whose endpoints coincide draws nothing.
+pub(crate) fn annular_sector(radius: f64, inner_radius: f64, start: f64, end: f64) -> String {
+    let sweep = end - start;
+    if sweep >= 2.0 * PI - 1e-9 {
+        let mid = start + PI;
+        return format!("{} {}", annular_sector(radius, inner_radius, start, mid), annular_sector(radius, inner_radius, mid, start + 2.0 * PI));
+    }
+    let x1 = start.cos() * radius;
+    let y1 = start.sin() * radius;
+    let x2 = end.cos() * radius;
+    let y2 = end.sin() * radius;
+    let x3 = end.cos() * inner_radius;
+    let y3 = end.sin() * inner_radius;
+    let x4 = start.cos() * inner_radius;
+    let y4 = start.sin() * inner_radius;
+
+    let large_arc = if sweep > PI { 1 } else { 0 };
+    format!("M {} {} A {} {} 0 {} 1 {} {} L {} {} A {} {} 0 {} 0 {} {} Z", x1, y1, radius, radius, large_arc, x2, y2, x3, y3, inner_radius, inner_radius, large_arc, x4, y4)
+}
+
+// Grid column and row for the `index`-th day of week `x`. Dated days sit on
+// their weekday's row, so a partial first week stays aligned; undated days
+// keep their position. A Monday start moves each Sunday to the bottom row of
+// the previous column, so every other day shifts one column right.
+pub(crate) fn cell_position(x: usize, index: usize, day: &Day, week_start: WeekStart) -> (usize, usize) {
+    let Some(date) = day.date else { return (x, index) };
+    let weekday = date.weekday() as usize;
+    match week_start {
+        WeekStart::Sunday => (x, weekday),
+        WeekStart::Monday if weekday == 0 => (x, 6),
+        WeekStart::Monday => (x + 1, weekday - 1),
+    }
+}
+
+// A bar's nodes keyed by paint order: depth (col + row), then height
+type DepthSorted = (usize, f64, Vec<Box<dyn Node>>);
+
+// Bars are painted back to front: a cell's screen depth grows with col + row,
+// and within a diagonal the shorter bar goes first. Raw calendar order can put
+// a bar ahead of one it stands in front of, e.g. when a Monday start moves a
+// week's Sunday into the previous column.
+fn draw_3d_heatmap(weeks: &[Week], opts: &Options) -> Group {
+    let proj = &opts.projection;
+    let scale = ColorScale::new(weeks, opts);
+    let mut cells: Vec<DepthSorted> = Vec::new();
+    for (x, week) in weeks.iter().enumerate() {
+        for (index, day) in week.contribution_days.iter().enumerate() {
+            let h = cell_height(day.contribution_count, opts);
+            let (col, row) = cell_position(x, index, day, opts.week_start);
+            let (xf, yf) = (col as f64, row as f64);
+            let color = day_fill(x, day, &scale, opts);
+
+            // With --bevel the side faces stop short of the top and four chamfer
+            // quads climb to an inset top face.
+            let drop = if opts.bevel { bevel_drop(h) } else { 0.0 };
+            let side_h = h - drop;
+            let p_top_back = proj.project(xf, yf, side_h);
+            let p_top_left = proj.project(xf + 1.0, yf, side_h);
+            let p_top_right = proj.project(xf, yf + 1.0, side_h);
+            let p_top_front = proj.project(xf + 1.0, yf + 1.0, side_h);
+            let p_bot_left = proj.project(xf + 1.0, yf, 0.0);
+            let p_bot_right = proj.project(xf, yf + 1.0, 0.0);
+            let p_bot_front = proj.project(xf + 1.0, yf + 1.0, 0.0);
+
+            let mut faces = vec![
+                Polygon::new().set("fill", darken(&color, 0.8)).set("points", points(&[p_top_left, p_top_front, p_bot_front, p_bot_left])),
+                Polygon::new().set("fill", darken(&color, 0.6)).set("points", points(&[p_top_right, p_top_front, p_bot_front, p_bot_right])),
+            ];
+            let top_corners = if drop > 0.0 {
+                let (lo, hi) = (BEVEL_INSET, 1.0 - BEVEL_INSET);
+                let inset = [proj.project(xf + lo, yf + lo, h), proj.project(xf + hi, yf + lo, h), proj.project(xf + hi, yf + hi, h), proj.project(xf + lo, yf + hi, h)];
+                // Back chamfers first; the front pair shares the side faces' shading, lifted.
+                for (outer, inner, shade) in [
+                    ([p_top_back, p_top_left], [inset[0], inset[1]], 0.95),
+                    ([p_top_right, p_top_back], [inset[3], inset[0]], 0.95),
+                    ([p_top_left, p_top_front], [inset[1], inset[2]], 0.9),
+                    ([p_top_front, p_top_right], [inset[2], inset[3]], 0.75),
+                ] {
+                    faces.push(Polygon::new().set("fill", darken(&color, shade)).set("points", points(&[outer[0], outer[1], inner[1], inner[0]])));
+                }
+                inset
+            } else {
+                [p_top_back, p_top_left, p_top_front, p_top_right]
+            };
+            let mut top = Polygon::new().set("fill", color.as_str()).set("points", points(&top_corners));
+            if scale.is_recent(day) {
+                top = top.set("stroke", opts.theme.accent.as_str()).set("stroke-width", stroke_width(RECENT_STROKE, opts)).set("stroke-linejoin", "round");
+            }
+            if is_clamped(day.contribution_count, opts) {
+                // Height no longer tells the story, so keep the true count on hover
+                top = top.add(Title::new().add(TextNode::new(format!("{} contributions", opts.locale.format_int(day.contribution_count as i64)))));
+            }
+            if opts.interactive {
+                // One group per bar so the hover script can highlight all faces at once
+                let mut bar = Group::new().set("class", "hm-bar").set("data-count", day.contribution_count);
+                if let Some(date) = day.date {
+                    bar = bar.set("data-date", date.to_string()).set("data-label", opts.locale.format_date(date));
+                }
+                for face in faces {
+                    bar = bar.add(face);
+                }
+                cells.push((col + row, h, vec![Box::new(bar.add(top))]));
+            } else {
+                let mut nodes: Vec<Box<dyn Node>> = faces.into_iter().map(|face| Box::new(face) as Box<dyn Node>).collect();
+                nodes.push(Box::new(top));
+                cells.push((col + row, h, nodes));
+            }
+        }
+    }
+    cells.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    cells.into_iter().flat_map(|(_, _, nodes)| nodes).fold(Group::new(), Group::add)
+}
+
+// Quarter steps up to the tallest drawn bar (after --clamp-count).
+fn height_samples(weeks: &[Week], opts: &Options) -> Vec<i32> {
+    let max = max_drawn_count(weeks, opts);
+    if max <= 0 {
+        return Vec::new();
+    }
+    let mut samples: Vec<i32> = (1..=4).map(|i| ((max * i) as f64 / 4.0).ceil() as i32).collect();
+    samples.dedup();
+    samples
+}
+
+// Sample bars at representative counts, drawn with the same height function
+// as the heatmap so the z-axis can be read off the card.
+fn draw_height_legend(weeks: &[Week], opts: &Options) -> Group {
+    let mut g = Group::new();
+    let samples = height_samples(weeks, opts);
+    if samples.is_empty() {
+        return g;
+    }
+
+    // Free space left of the heatmap's near corner
+    let proj = Projection { origin_x: 70.0, origin_y: 330.0, angle_deg: opts.projection.angle_deg, scale: 12.0, mirrored: opts.projection.mirrored };
+    let (tx, ty) = proj.project(0.0, 0.0, 0.0);
+    let anchor = if proj.mirrored { "end" } else { "start" };
+    g = g.add(SvgText::new().set("x", tx).set("y", ty + 60.0).set("fill", opts.theme.text.as_str()).set("text-anchor", anchor).set("font-size", 13).add(TextNode::new("Bar height")));
+    for (i, &count) in samples.iter().enumerate() {
+        let (xf, h) = (i as f64 * 1.6, cell_height(count, opts));
+        let top = [proj.project(xf, 0.0, h), proj.project(xf + 1.0, 0.0, h), proj.project(xf + 1.0, 1.0, h), proj.project(xf, 1.0, h)];
+        let bottom = [proj.project(xf + 1.0, 0.0, 0.0), proj.project(xf + 1.0, 1.0, 0.0), proj.project(xf, 1.0, 0.0)];
+        let color = opts.theme.accent.as_str();
+        g = g.add(Polygon::new().set("fill", darken(color, 0.8)).set("points", points(&[top[1], top[2], bottom[1], bottom[0]])));
+        g = g.add(Polygon::new().set("fill", darken(color, 0.6)).set("points", points(&[top[3], top[2], bottom[1], bottom[2]])));
+        g = g.add(Polygon::new().set("fill", color).set("points", points(&top)));
+        let (lx, ly) = proj.project(xf + 1.0, 1.0, 0.0);
+        g = g.add(SvgText::new().set("x", lx).set("y", ly + 16.0).set("fill", opts.theme.text.as_str()).set("text-anchor", "middle").set("font-size", 11).add(TextNode::new(opts.locale.format_int(count as i64))));
+    }
+    g
+}
+
+/// Most entries `--top-days` fits in the free corner above the heatmap.
+pub const MAX_TOP_DAYS: usize = 10;
+
+// --top-days leaderboard, in the empty corner above the heatmap's far end
+// (top left, or top right when mirrored).
+fn draw_top_days(weeks: &[Week], opts: &Options) -> Group {
+    let (x, anchor) = if opts.projection.mirrored { (VIEW_WIDTH - GRID_MARGIN, "end") } else { (GRID_MARGIN, "start") };
+    let mut g = Group::new().add(SvgText::new().set("x", x).set("y", 60).set("fill", opts.theme.text.as_str()).set("text-anchor", anchor).set("font-size", 15).set("font-weight", "bold").add(TextNode::new("Busiest days")));
+    for (i, (date, count)) in top_days(weeks, opts.top_days).into_iter().enumerate() {
+        let line = format!("{}. {} · {}", i + 1, opts.locale.format_date(date), opts.locale.format_int(count as i64));
+        g = g.add(SvgText::new().set("x", x).set("y", 84.0 + i as f64 * 20.0).set("fill", opts.theme.text.as_str()).set("text-anchor", anchor).set("font-size", 13).add(TextNode::new(line)));
+    }
+    g
+}
+
+/// Most repositories the `repo-langs` panel lists.
+pub const MAX_REPO_LANGS: usize = 10;
+// Longer repository names are cut to this many characters plus an ellipsis
+const REPO_NAME_CHARS: usize = 20;
+const REPO_LANGS_Y: f64 = 430.0;
+const REPO_LANGS_ROW: f64 = 20.0;
+// Estimated width of a full row: dot, 20-character name and language
+const REPO_LANGS_WIDTH: f64 = 200.0;
+
+fn truncated(name: &str, max: usize) -> String {
+    if name.chars().count() <= max { name.to_string() } else { format!("{}…", name.chars().take(max - 1).collect::<String>()) }
+}
+
+// --panels repo-langs: each repository's primary language, most starred
+// first, in the free column left of the grid (right when mirrored).
+fn draw_repo_languages(repos: &[RepoLanguage], opts: &Options) -> Group {
+    let mirrored = opts.projection.mirrored;
+    let (x, anchor) = if mirrored { (VIEW_WIDTH - GRID_MARGIN, "end") } else { (GRID_MARGIN, "start") };
+    let mut g = Group::new().add(SvgText::new().set("x", x).set("y", REPO_LANGS_Y).set("fill", opts.theme.text.as_str()).set("text-anchor", anchor).set("font-size", 15).set("font-weight", "bold").add(TextNode::new("Repositories")));
+    for (i, repo) in repos.iter().enumerate() {
+        let y = REPO_LANGS_Y + 24.0 + i as f64 * REPO_LANGS_ROW;
+        let (dot_x, text_x) = if mirrored { (x - 5.0, x - 16.0) } else { (x + 5.0, x + 16.0) };
+        let line = format!("{} · {}", truncated(&repo.repo, REPO_NAME_CHARS), repo.language);
+        g = g
+            .add(Circle::new().set("cx", dot_x).set("cy", y - 4.5).set("r", 5).set("fill", repo.color.as_str()))
+            .add(SvgText::new().set("x", text_x).set("y", y).set("fill", opts.theme.text.as_str()).set("text-anchor", anchor).set("font-size", 13).add(TextNode::new(escape_text(&line))));
+    }
+    g
+}
+
+// Title row for --show-name / --show-avatar, centered above the card. Text
+// width is estimated from the character count since SVG can't measure it.
+const AVATAR_SIZE: f64 = 40.0;
+const NAME_FONT_SIZE: f64 = 22.0;
+
+fn draw_profile_header(user: &User, opts: &Options) -> Group {
+    let name = user.name.as_deref().map(str::trim).filter(|n| !n.is_empty()).unwrap_or(&user.login);
+    let name = if opts.show_name && !name.is_empty() { Some(name) } else { None };
+    let href = opts.avatar.as_deref().or(user.avatar_url.as_deref()).filter(|_| opts.show_avatar);
+
+    let avatar_width = if href.is_some() { AVATAR_SIZE + 12.0 } else { 0.0 };
+    let text_width = name.map_or(0.0, |n| n.chars().count() as f64 * NAME_FONT_SIZE * 0.6);
+    let x = (VIEW_WIDTH - avatar_width - text_width) / 2.0;
+    let mut g = Group::new();
+    if let Some(href) = href {
+        g = g.add(Image::new().set("href", escape_attr(href)).set("x", x).set("y", 16).set("width", AVATAR_SIZE).set("height", AVATAR_SIZE).set("clip-path", format!("url(#{})", AVATAR_CLIP_ID)));
+    }
+    if let Some(name) = name {
+        g = g.add(SvgText::new().set("x", x + avatar_width).set("y", 16.0 + AVATAR_SIZE / 2.0 + NAME_FONT_SIZE * 0.35).set("fill", opts.theme.text.as_str()).set("font-size", NAME_FONT_SIZE).set("font-weight", "bold").add(TextNode::new(escape_text(name))));
+    }
+    g
+}
+
+// Ground-plane separators every --week-ticks weeks, drawn before the bars so
+// they only show between them.
+fn draw_week_ticks(weeks: &[Week], opts: &Options) -> Group {
+    let mut g = Group::new();
+    if opts.week_ticks == 0 {
+        return g;
+    }
+    let proj = &opts.projection;
+    let rows = weeks.iter().map(|w| w.contribution_days.len()).max().unwrap_or(0) as f64;
+    for x in (opts.week_ticks..weeks.len()).step_by(opts.week_ticks) {
+        let ((x1, y1), (x2, y2)) = (proj.project(x as f64, 0.0, 0.0), proj.project(x as f64, rows, 0.0));
+        g = g.add(Path::new().set("d", format!("M {} {} L {} {}", x1, y1, x2, y2)).set("stroke", opts.theme.grid.as_str()).set("stroke-width", stroke_width(1.0, opts)));
+    }
+    g
+}
+
+// Week labels go on every this many columns to avoid clutter
+const WEEK_LABEL_EVERY: usize = 4;
+
+// Each grid column's dates, in row order.
+fn column_dates(weeks: &[Week], week_start: WeekStart) -> BTreeMap<usize, Vec<(usize, Date)>> {
+    let mut cols: BTreeMap<usize, Vec<(usize, Date)>> = BTreeMap::new();
+    for (x, week) in weeks.iter().enumerate() {
+        for (index, day) in week.contribution_days.iter().enumerate() {
+            let Some(date) = day.date else { continue };
+            let (col, row) = cell_position(x, index, day, week_start);
+            cols.entry(col).or_default().push((row, date));
+        }
+    }
+    for dates in cols.values_mut() {
+        dates.sort();
+    }
+    cols
+}
+
+// Month labels closer than this many columns give way to the later one, so a
+// sliver of a month at the start doesn't collide with the next name.
+const MONTH_LABEL_MIN_GAP: usize = 3;
+
+// Column labels just behind the grid's back edge: a month name where a new
+// month begins, or an ISO week number every few columns.
+fn column_labels(weeks: &[Week], opts: &Options) -> Vec<(usize, String)> {
+    let mut labels: Vec<(usize, String)> = Vec::new();
+    let mut previous_month = None;
+    for (col, dates) in column_dates(weeks, opts.week_start) {
+        match opts.axis_labels {
+            AxisLabels::None => break,
+            AxisLabels::Month => {
+                let first = dates[0].1;
+                if previous_month == Some((first.year, first.month)) {
+                    continue;
+                }
+                previous_month = Some((first.year, first.month));
+                if labels.last().is_some_and(|(last, _)| last + MONTH_LABEL_MIN_GAP > col) {
+                    labels.pop();
+                }
+                labels.push((col, opts.locale.month_abbr(first.month).to_string()));
+            }
+            AxisLabels::Week if col % WEEK_LABEL_EVERY == 0 => {
+                // A Sunday-first column holds the tail of the previous ISO week
+                // in its Sunday; any other day names the column's week.
+                let date = dates.iter().map(|(_, d)| *d).find(|d| d.weekday() != 0).unwrap_or(dates[0].1);
+                labels.push((col, format!("W{}", date.iso_week().1)));
+            }
+            AxisLabels::Week => {}
+        }
+    }
+    labels
+}
+
+// Row labels in front of the first column for Monday, Wednesday and Friday,
+// like GitHub's calendar; shown whenever column labels are.
+fn row_labels(opts: &Options) -> Vec<(usize, &'static str)> {
+    if opts.axis_labels == AxisLabels::None {
+        return Vec::new();
+    }
+    let offset = if opts.week_start == WeekStart::Monday { 1 } else { 0 };
+    [1, 3, 5].into_iter().map(|weekday| ((weekday - offset) as usize, opts.locale.weekday_abbr(weekday))).collect()
+}
+
+fn draw_axis_labels(weeks: &[Week], opts: &Options) -> Group {
+    let mut g = Group::new();
+    for (col, label) in column_labels(weeks, opts) {
+        let (x, y) = opts.projection.project(col as f64 + 0.5, -0.8, 0.0);
+        g = g.add(SvgText::new().set("x", x).set("y", y).set("fill", opts.theme.text.as_str()).set("text-anchor", "middle").set("font-size", 12).add(TextNode::new(label)));
+    }
+    let anchor = if opts.projection.mirrored { "start" } else { "end" };
+    for (row, label) in row_labels(opts) {
+        let (x, y) = opts.projection.project(-0.4, row as f64 + 0.5, 0.0);
+        g = g.add(SvgText::new().set("x", x).set("y", y + 4.0).set("fill", opts.theme.text.as_str()).set("text-anchor", anchor).set("font-size", 12).add(TextNode::new(label)));
+    }
+    g
+}
+
+// --year-separators: a stepped ground line along each January 1 boundary
+// (which can fall mid-column), with the new year printed beyond the back edge.
+fn draw_year_separators(weeks: &[Week], opts: &Options) -> Group {
+    let proj = &opts.projection;
+    let mut g = Group::new();
+    for (col, dates) in column_dates(weeks, opts.week_start) {
+        let Some(&(row, date)) = dates.iter().find(|(_, d)| d.month == 1 && d.day == 1) else { continue };
+        let (c, r) = (col as f64, row as f64);
+        let corners = if row == 0 { vec![(c, 0.0), (c, 7.0)] } else { vec![(c + 1.0, 0.0), (c + 1.0, r), (c, r), (c, 7.0)] };
+        let d: Vec<String> = corners.iter().enumerate().map(|(i, &(x, y))| {
+            let (sx, sy) = proj.project(x, y, 0.0);
+            format!("{} {} {}", if i == 0 { "M" } else { "L" }, sx, sy)
+        }).collect();
+        g = g.add(Path::new().set("d", d.join(" ")).set("fill", "none").set("stroke", opts.theme.text.as_str()).set("stroke-width", stroke_width(1.5, opts)).set("stroke-dasharray", "4 3"));
+        let (lx, ly) = proj.project(c + 0.5, -2.2, 0.0);
+        g = g.add(SvgText::new().set("x", lx).set("y", ly).set("fill", opts.theme.text.as_str()).set("text-anchor", "middle").set("font-size", 14).set("font-weight", "bold").add(TextNode::new(date.year.to_string())));
+    }
+    g
+}
+
+// --granularity week: each week collapses into a single undated cell holding
+// the week's total, giving a one-row heatmap.
+fn weekly_totals(weeks: &[Week]) -> Vec<Week> {
+    week_totals(weeks).into_iter().map(|total| Week { contribution_days: vec![Day { contribution_count: total, date: None }] }).collect()
+}
+
+const PLACEHOLDER_TEXT: &str = "No contribution data for this period";
+
+// Stand-in for an empty calendar (new account, restricted token): a faint
+// flat grid with an explanatory label instead of blank space.
+fn draw_empty_heatmap(opts: &Options) -> Group {
+    let proj = &opts.projection;
+    let (cols, rows) = (26, 7);
+    let mut g = Group::new();
+    for x in 0..cols {
+        for y in 0..rows {
+            let (xf, yf) = (x as f64, y as f64);
+            let corners = [proj.project(xf, yf, 0.0), proj.project(xf + 1.0, yf, 0.0), proj.project(xf + 1.0, yf + 1.0, 0.0), proj.project(xf, yf + 1.0, 0.0)];
+            let points: Vec<String> = corners.iter().map(|(px, py)| format!("{},{}", px, py)).collect();
+            g = g.add(Polygon::new().set("points", points.join(" ")).set("fill", opts.theme.empty_cell.as_str()).set("stroke", opts.theme.background.as_str()).set("stroke-width", stroke_width(1.0, opts)));
+        }
+    }
+    let (lx, ly) = proj.project(cols as f64 / 2.0, rows as f64 / 2.0, 40.0);
+    g.add(SvgText::new().set("x", lx).set("y", ly).set("fill", opts.theme.text.as_str()).set("text-anchor", "middle").set("font-size", 18).add(TextNode::new(PLACEHOLDER_TEXT)))
+}
+
+/// Default for `--cell-radius`, close to GitHub's own calendar squares.
+pub const DEFAULT_CELL_RADIUS: f64 = 0.18;
+
+// Share of each grid square a flat cell fills; the rest is the gutter.
+const FLAT_CELL_SIZE: f64 = 0.85;
+
+// The projection restricted to the ground plane is affine, so flat cells are
+// plain rounded `Rect`s in grid units under one matrix transform; the corner
+// radius then scales with the cell like the rest of the geometry.
+fn draw_flat_heatmap(weeks: &[Week], opts: &Options) -> Group {
+    let proj = &opts.projection;
+    let (ox, oy) = proj.project(0.0, 0.0, 0.0);
+    let (ax, ay) = proj.project(1.0, 0.0, 0.0);
+    let (bx, by) = proj.project(0.0, 1.0, 0.0);
+    let scale = ColorScale::new(weeks, opts);
+    let inset = (1.0 - FLAT_CELL_SIZE) / 2.0;
+    let radius = opts.cell_radius * FLAT_CELL_SIZE;
+    let mut g = Group::new().set("transform", format!("matrix({} {} {} {} {} {})", ax - ox, ay - oy, bx - ox, by - oy, ox, oy));
+    for (x, week) in weeks.iter().enumerate() {
+        for (index, day) in week.contribution_days.iter().enumerate() {
+            let (col, row) = cell_position(x, index, day, opts.week_start);
+            let color = day_fill(x, day, &scale, opts);
+            let mut cell = Rectangle::new()
+                .set("x", col as f64 + inset).set("y", row as f64 + inset)
+                .set("width", FLAT_CELL_SIZE).set("height", FLAT_CELL_SIZE)
+                .set("rx", radius).set("ry", radius)
+                .set("fill", color.as_str());
+            if scale.is_recent(day) {
+                // Cells are drawn in grid units, so keep the outline in pixels
+                cell = cell.set("stroke", opts.theme.accent.as_str()).set("stroke-width", RECENT_STROKE).set("vector-effect", "non-scaling-stroke");
+            }
+            g = g.add(cell);
+        }
+    }
+    g
+}
+
+// Triangulates the quads between adjacent day centers into a continuous mesh,
+// drawn back-to-front (ascending x + y) and shaded by height.
+fn draw_3d_surface(weeks: &[Week], opts: &Options) -> Group {
+    let proj = &opts.projection;
+    let mut g = Group::new();
+    // Heights keyed by grid position, so --week-start moves the mesh the same
+    // way it moves the bars.
+    let mut grid: BTreeMap<(usize, usize), (f64, i32, usize)> = BTreeMap::new();
+    for (x, week) in weeks.iter().enumerate() {
+        for (index, day) in week.contribution_days.iter().enumerate() {
+            let pos = cell_position(x, index, day, opts.week_start);
+            grid.insert(pos, (bar_height(day.contribution_count, opts), day.contribution_count, x));
+        }
+    }
+    let height_at = |col: usize, row: usize| grid.get(&(col, row)).map(|&(h, count, _)| (h, count));
+    let max_h = weeks.iter().flat_map(|w| &w.contribution_days).map(|d| bar_height(d.contribution_count, opts)).fold(0.0, f64::max);
+    let scale = ColorScale::new(weeks, opts);
+
+    let mut quads = Vec::new();
+    for (&(col, row), &(_, _, x)) in &grid {
+        if let (Some(a), Some(b), Some(c), Some(d)) = (height_at(col, row), height_at(col + 1, row), height_at(col, row + 1), height_at(col + 1, row + 1)) {
+            quads.push((col, row, x, [a, b, c, d]));
+        }
+    }
+    quads.sort_by_key(|(col, row, _, _)| col + row);
+
+    for (col, row, x, [a, b, c, d]) in quads {
+        let (xf, yf) = (col as f64 + 0.5, row as f64 + 0.5);
+        let back = proj.project(xf, yf, a.0);
+        let left = proj.project(xf + 1.0, yf, b.0);
+        let right = proj.project(xf, yf + 1.0, c.0);
+        let front = proj.project(xf + 1.0, yf + 1.0, d.0);
+
+        for (tri, corners) in [([back, left, right], [a, b, c]), ([left, front, right], [b, d, c])] {
+            let avg_h = corners.iter().map(|c| c.0).sum::<f64>() / 3.0;
+            let max_count = corners.iter().map(|c| c.1).max().unwrap_or(0);
+            let shade = if max_h > 0.0 { 0.6 + 0.4 * (avg_h / max_h) } else { 1.0 };
+            let color = darken(&cell_color(x, max_count, &scale, opts), shade);
+            let points = format!("{},{} {},{} {},{}", tri[0].0, tri[0].1, tri[1].0, tri[1].1, tri[2].0, tri[2].1);
+            g = g.add(Polygon::new().set("fill", color.as_str()).set("stroke", color.as_str()).set("stroke-width", stroke_width(0.5, opts)).set("points", points));
+        }
+    }
+    g
+}
+
+// Drawn slice angles in radians. With a minimum, slices below it are widened
+// and the difference is taken from the larger slices in proportion to their
+// room above the minimum, so the total turn is unchanged. Labels keep the
+// true percentages.
+fn slice_angles(percents: &[f64], min_degrees: Option<f64>) -> Vec<f64> {
+    let angles: Vec<f64> = percents.iter().map(|p| p / 100.0 * 2.0 * PI).collect();
+    let Some(min) = min_degrees.map(f64::to_radians) else { return angles };
+    let total: f64 = angles.iter().sum();
+    if min * angles.len() as f64 >= total {
+        return vec![total / angles.len().max(1) as f64; angles.len()];
+    }
+    let deficit: f64 = angles.iter().map(|a| (min - a).max(0.0)).sum();
+    let room: f64 = angles.iter().map(|a| (a - min).max(0.0)).sum();
+    angles.iter().map(|&a| if a < min { min } else { a - (a - min) / room * deficit }).collect()
+}
+
+// Languages under --other-below folded into one trailing "Other" slice, so
+// the legend never lists a share that rounds to nothing.
+fn donut_slices(langs: &[LanguageStat], opts: &Options) -> Vec<LanguageStat> {
+    let (mut slices, minor): (Vec<LanguageStat>, Vec<LanguageStat>) = langs.iter().cloned().partition(|l| l.percent >= opts.other_below);
+    if !minor.is_empty() {
+        slices.push(LanguageStat {
+            name: "Other".to_string(),
+            bytes: minor.iter().map(|l| l.bytes).sum(),
+            percent: minor.iter().map(|l| l.percent).sum(),
+            color: OTHER_COLOR.to_string(),
+        });
+    }
+    slices
+}
+
+// Percentages in tenths, rounded by largest remainder so the legend adds up
+// to the same total as the unrounded shares (100.0% for a non-empty donut).
+fn rounded_percents(percents: &[f64]) -> Vec<u32> {
+    let target = (percents.iter().sum::<f64>() * 10.0).round() as u32;
+    let mut tenths: Vec<u32> = percents.iter().map(|p| (p * 10.0).floor() as u32).collect();
+    let mut by_remainder: Vec<usize> = (0..percents.len()).collect();
+    by_remainder.sort_by(|&a, &b| (percents[b] * 10.0).fract().total_cmp(&(percents[a] * 10.0).fract()).then(a.cmp(&b)));
+    let short = target.saturating_sub(tenths.iter().sum()) as usize;
+    for &i in by_remainder.iter().take(short) {
+        tenths[i] += 1;
+    }
+    tenths
+}
+
+// Legend text per slice: the name and its share, plus the size with
+// --legend-sizes.
+fn legend_labels(slices: &[LanguageStat], opts: &Options) -> Vec<String> {
+    let percents: Vec<f64> = slices.iter().map(|l| l.percent).collect();
+    slices
+        .iter()
+        .zip(rounded_percents(&percents))
+        .map(|(lang, tenths)| {
+            let share = opts.locale.format_percent(tenths);
+            if opts.legend_sizes { format!("{} {} · {}", lang.name, share, lang.size_label()) } else { format!("{} {}", lang.name, share) }
+        })
+        .collect()
+}
+
+pub(crate) fn draw_donut_chart(langs: &[LanguageStat], opts: &Options) -> Group {
+    let panels = PanelLayout::new(opts);
+    let (cx, cy) = panels.donut_center;
+    let mut g = Group::new().set("transform", format!("translate({}, {})", cx, cy));
+    let mut current_angle: f64 = 0.0;
+    let slices = donut_slices(langs, opts);
+    let labels = legend_labels(&slices, opts);
+    let legend = LegendLayout::new(&labels, opts.legend_cols, &panels);
+
+    let percents: Vec<f64> = slices.iter().map(|l| l.percent).collect();
+    let angles = slice_angles(&percents, opts.min_slice_angle);
+
+    for (i, ((lang, label), &slice_angle)) in slices.iter().zip(&labels).zip(&angles).enumerate() {
+        // GitHub's language colors often pair red with green, so --cvd-safe
+        // assigns the qualitative palette by rank instead
+        let color = if opts.cvd_safe { CVD_QUALITATIVE[i % CVD_QUALITATIVE.len()] } else { lang.color.as_str() };
+        let d = annular_sector(DONUT_RADIUS, DONUT_INNER_RADIUS, current_angle, current_angle + slice_angle);
+        let mut slice = Path::new().set("d", d).set("fill", color);
+        if opts.donut_stroke {
+            // Background-colored separator so similar hues stay distinguishable
+            slice = slice.set("stroke", opts.theme.background.as_str()).set("stroke-width", stroke_width(1.5, opts));
+        }
+        g = g.add(slice);
+        
+        // Dynamic multi-column legend
+        let (x_off, y_off) = legend.position(i);
+
+        g = g.add(Polygon::new().set("points", "0,0 12,0 12,12 0,12").set("fill", color).set("transform", format!("translate({}, {})", x_off, y_off)));
+        g = g.add(SvgText::new().set("x", x_off + LEGEND_LABEL_X).set("y", y_off + 10.0).set("fill", opts.theme.text.as_str()).set("font-size", legend.font_size).add(TextNode::new(escape_text(label))));
+        
+        current_angle += slice_angle;
+    }
+    g
+}
+
+// --goal-ring track, inside the donut's hole
+const GOAL_RING_RADIUS: f64 = 52.0;
+const GOAL_RING_WIDTH: f64 = 8.0;
+
+// Share of the period's goal reached: the calendar's total against the daily
+// goal for every day it covers. Not capped, so a busy year reads over 100%.
+fn goal_progress(weeks: &[Week], goal: i32) -> f64 {
+    let days = weeks.iter().map(|w| w.contribution_days.len()).sum::<usize>();
+    let total: i64 = weeks.iter().flat_map(|w| &w.contribution_days).map(|d| d.contribution_count.max(0) as i64).sum();
+    if days == 0 { 0.0 } else { total as f64 / (goal as f64 * days as f64) }
+}
+
+// --goal-ring: a track with the progress arc from twelve o'clock, clockwise,
+// and the percentage in the middle, centered on the donut.
+fn draw_goal_ring(weeks: &[Week], goal: i32, opts: &Options) -> Group {
+    let (cx, cy) = PanelLayout::new(opts).donut_center;
+    let progress = goal_progress(weeks, goal);
+    let (outer, inner) = (GOAL_RING_RADIUS, GOAL_RING_RADIUS - GOAL_RING_WIDTH);
+    let start = -PI / 2.0;
+    let mut g = Group::new()
+        .set("transform", format!("translate({}, {})", cx, cy))
+        .add(Path::new().set("d", annular_sector(outer, inner, start, start + 2.0 * PI)).set("fill", opts.theme.grid.as_str()));
+    if progress > 0.0 {
+        g = g.add(Path::new().set("d", annular_sector(outer, inner, start, start + 2.0 * PI * progress.min(1.0))).set("fill", GOAL_MET));
+    }
+    let label = format!("{}%", opts.locale.format_int((progress * 100.0).round() as i64));
+    g.add(SvgText::new().set("x", 0).set("y", 6).set("fill", opts.theme.text.as_str()).set("text-anchor", "middle").set("font-size", 16).set("font-weight", "bold").add(TextNode::new(label)))
+}
+
+/// Reference values for `--radar-benchmark`, one per radar axis (commits,
+/// issues, pull requests, reviews, repositories): roughly a busy year.
+pub const DEFAULT_RADAR_BENCHMARK: [i32; 5] = [1000, 100, 200, 200, 20];
+
+// Fraction of the radar radius for an axis value, before capping at 1. By
+// default a log scale (10,000 reaches the rim); with a benchmark, linear
+// against its reference so radars are comparable across users.
+fn radar_scale(val: i32, axis: usize, opts: &Options) -> f64 {
+    match opts.radar_benchmark {
+        None => (val as f64 + 1.0).log10() / 4.0,
+        Some(refs) => val.max(0) as f64 / refs[axis].max(1) as f64,
+    }
+}
+
+const RADAR_RADIUS: f64 = 110.0;
+// Room for the axis labels drawn past the outer ring
+const RADAR_LABEL_MARGIN: f64 = 40.0;
+
+fn draw_radar_chart(stats: &[i32; 5], opts: &Options) -> Group {
+    let (cx, cy) = PanelLayout::new(opts).radar_center;
+    let mut g = Group::new().set("transform", format!("translate({}, {})", cx, cy));
+    let labels = ["Commit", "Issue", "PullReq", "Review", "Repo"];
+    let max_r = RADAR_RADIUS;
+    
+    for r in [0.25, 0.5, 0.75, 1.0] {
+        let mut points = String::new();
+        for i in 0..5 {
+            let a = (i as f64 * 72.0 - 90.0).to_radians();
+            points.push_str(&format!("{},{} ", a.cos() * max_r * r, a.sin() * max_r * r));
+        }
+        g = g.add(Polygon::new().set("points", points).set("fill", "none").set("stroke", opts.theme.grid.as_str()).set("stroke-width", stroke_width(1.0, opts)));
+    }
+
+    let mut data_points = String::new();
+    for (i, &val) in stats.iter().enumerate() {
+        let val_scaled = radar_scale(val, i, opts);
+        let a = (i as f64 * 72.0 - 90.0).to_radians();
+        let r = val_scaled.min(1.0) * max_r;
+        data_points.push_str(&format!("{},{} ", a.cos() * r, a.sin() * r));
+        g = g.add(SvgText::new().set("x", a.cos() * 140.0 - 25.0).set("y", a.sin() * 140.0).set("fill", opts.theme.text.as_str()).set("font-size", 15).add(TextNode::new(labels[i])));
+    }
+    g.add(Polygon::new().set("points", data_points).set("fill", opts.theme.accent.as_str()).set("fill-opacity", opts.radar_opacity).set("stroke", opts.theme.accent.as_str()).set("stroke-width", stroke_width(2.0, opts)))
+}
+
+// Browser-only hover behavior for --interactive. Reads the data-* attributes set
+// on each bar; where scripts don't run (e.g. <img> embeds) the card stays static.
+const HOVER_SCRIPT: &str = r#"(function () {
+  var svg = document.querySelector('svg'), tip = document.getElementById('hm-tip'), label = document.getElementById('hm-tip-text');
+  if (!svg || !tip || !label) return;
+  document.querySelectorAll('.hm-bar').forEach(function (bar) {
+    bar.addEventListener('mouseenter', function () {
+      bar.setAttribute('opacity', '0.7');
+      var date = bar.getAttribute('data-label') || bar.getAttribute('data-date');
+      label.textContent = (date ? date + ': ' : '') + bar.getAttribute('data-count') + ' contributions';
+      tip.setAttribute('visibility', 'visible');
+    });
+    bar.addEventListener('mousemove', function (e) {
+      var p = svg.createSVGPoint();
+      p.x = e.clientX; p.y = e.clientY;
+      p = p.matrixTransform(svg.getScreenCTM().inverse());
+      tip.setAttribute('transform', 'translate(' + (p.x + 12) + ',' + (p.y - 28) + ')');
+    });
+    bar.addEventListener('mouseleave', function () {
+      bar.removeAttribute('opacity');
+      tip.setAttribute('visibility', 'hidden');
+    });
+  });
+})();"#;
+
+// Hidden info box plus the script that drives it; added last so it paints on
+// top. The box inverts the theme, text color behind background-colored text,
+// so it stands out from the card on light and dark themes alike.
+fn draw_hover_layer(opts: &Options) -> (Group, Script) {
+    let tip = Group::new().set("id", "hm-tip").set("visibility", "hidden").set("pointer-events", "none")
+        .add(Rectangle::new().set("width", 200).set("height", 24).set("rx", 4).set("fill", opts.theme.text.as_str()).set("opacity", 0.9))
+        .add(SvgText::new().set("id", "hm-tip-text").set("x", 8).set("y", 16).set("fill", opts.theme.background.as_str()).set("font-size", 13));
+    let script = Script::new(format!("<![CDATA[\n{}\n]]>", HOVER_SCRIPT)).set("type", "application/ecmascript");
+    (tip, script)
+}
+
+// Full-canvas layer for --bg-gradient / --bg-image; a flat background is just
+// the root style's color.
+fn draw_background(opts: &Options) -> Option<Group> {
+    let layer = Group::new();
+    match &opts.background {
+        Background::Flat => None,
+        Background::Gradient(_) => Some(layer.add(Rectangle::new().set("width", VIEW_WIDTH).set("height", VIEW_HEIGHT).set("fill", format!("url(#{})", BACKGROUND_GRADIENT_ID)))),
+        Background::Image(url) => Some(layer.add(Image::new().set("href", escape_attr(url)).set("width", VIEW_WIDTH).set("height", VIEW_HEIGHT).set("preserveAspectRatio", "xMidYMid slice"))),
+    }
+}
+
+// Below this WCAG ratio the gray labels are hard to read over the background
+const MIN_LABEL_CONTRAST: f64 = 3.0;
+
+/// Legibility warnings for the chosen background: gradient stops too close to
+/// the label color. Image backgrounds can't be checked and get a reminder.
+pub fn background_warnings(opts: &Options) -> Vec<String> {
+    match &opts.background {
+        Background::Flat => Vec::new(),
+        Background::Gradient(stops) => stops
+            .iter()
+            .filter_map(|stop| {
+                let ratio = contrast_ratio(stop, &opts.theme.text)?;
+                (ratio < MIN_LABEL_CONTRAST).then(|| format!("background color {} has low contrast with the labels ({:.1}:1)", stop, ratio))
+            })
+            .collect(),
+        Background::Image(_) => vec!["label contrast isn't checked for --bg-image; make sure text stays readable".to_string()],
+    }
+}
+
+/// Default for `--max-canvas-bars`: about thirteen years of days.
+pub const DEFAULT_MAX_CANVAS_BARS: usize = 5000;
+
+/// Fails when the heatmap would draw more cells than `--max-canvas-bars`,
+/// before a huge range turns into a multi-megabyte file.
+pub fn check_canvas_size(weeks: &[Week], opts: &Options) -> Result<(), String> {
+    let cells = match opts.granularity {
+        Granularity::Day => weeks.iter().map(|w| w.contribution_days.len()).sum(),
+        Granularity::Week => weeks.len(),
+    };
+    if cells > opts.max_canvas_bars {
+        return Err(format!(
+            "the heatmap would draw {} cells, over the --max-canvas-bars limit of {}; narrow the date range, use --granularity week, or raise the limit",
+            cells, opts.max_canvas_bars
+        ));
+    }
+    Ok(())
+}
+
+// Right-hand margin the grid keeps from the canvas edge
+const GRID_MARGIN: f64 = 40.0;
+
+// A long range (e.g. a multi-decade --all-time card) projects far past the
+// canvas, into coordinates some renderers handle poorly. When the grid's far
+// corner would leave the canvas, shrink the projection scale until it fits;
+// ranges that already fit keep the configured projection.
+fn fitted_projection(weeks: &[Week], opts: &Options) -> Option<Projection> {
+    let proj = opts.projection;
+    let cols = weeks.len() as f64 + if opts.week_start == WeekStart::Monday { 1.0 } else { 0.0 };
+    let step = proj.angle_deg.to_radians().cos() * cols;
+    if opts.layout != HeatmapLayout::Grid || step <= 0.0 {
+        return None;
+    }
+    let fit = (VIEW_WIDTH - GRID_MARGIN - proj.origin_x) / step;
+    (fit < proj.scale && fit > 0.0).then_some(Projection { scale: fit, ..proj })
+}
+
+// Screen-space extent of the grid: every cell's floor corners and bar top.
+fn projected_bounds(weeks: &[Week], opts: &Options) -> Option<((f64, f64), (f64, f64))> {
+    let proj = &opts.projection;
+    let mut bounds: Option<((f64, f64), (f64, f64))> = None;
+    for (x, week) in weeks.iter().enumerate() {
+        for (index, day) in week.contribution_days.iter().enumerate() {
+            let (col, row) = cell_position(x, index, day, opts.week_start);
+            let (xf, yf) = (col as f64, row as f64);
+            let h = cell_height(day.contribution_count, opts);
+            for (dx, dy) in [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0)] {
+                for z in [0.0, h] {
+                    let (px, py) = proj.project(xf + dx, yf + dy, z);
+                    let ((min_x, min_y), (max_x, max_y)) = bounds.unwrap_or(((px, py), (px, py)));
+                    bounds = Some(((min_x.min(px), min_y.min(py)), (max_x.max(px), max_y.max(py))));
+                }
+            }
+        }
+    }
+    bounds
+}
+
+// Steep angles, large scales or very tall bars can project the grid above or
+// left of the canvas origin, where the viewBox would clip it. Returns how far
+// to move it right and down so it keeps GRID_MARGIN from those edges; the
+// canvas grows by the same amount so nothing is pushed off the other side.
+fn content_shift(weeks: &[Week], opts: &Options) -> (f64, f64) {
+    if opts.layout != HeatmapLayout::Grid {
+        return (0.0, 0.0);
+    }
+    let Some(((min_x, min_y), _)) = projected_bounds(weeks, opts) else { return (0.0, 0.0) };
+    ((GRID_MARGIN - min_x).max(0.0), (GRID_MARGIN - min_y).max(0.0))
+}
+
+// Moves the projection's output by a screen-space offset; the origin moves
+// the other way in a mirrored projection.
+fn shifted_projection(proj: &Projection, dx: f64, dy: f64) -> Projection {
+    let origin_x = if proj.mirrored { proj.origin_x - dx } else { proj.origin_x + dx };
+    Projection { origin_x, origin_y: proj.origin_y + dy, ..*proj }
+}
+
+// Default pixel size for --mini; the viewBox is square, so any size scales cleanly.
+const MINI_SIZE: u32 = 128;
+
+// --mini: the heatmap alone as flat cells (3D bars blur together at favicon
+// sizes; color carries the signal), fitted into a square viewBox.
+fn render_mini(weeks: &[Week], opts: &Options) -> Document {
+    let proj = Projection { origin_x: 0.0, origin_y: 0.0, angle_deg: opts.projection.angle_deg, scale: 10.0, mirrored: opts.projection.mirrored };
+    let mut g = Group::new();
+    let scale = ColorScale::new(weeks, opts);
+    let (mut min, mut max) = ((f64::MAX, f64::MAX), (f64::MIN, f64::MIN));
+    for (x, week) in weeks.iter().enumerate() {
+        for (index, day) in week.contribution_days.iter().enumerate() {
+            let (col, row) = cell_position(x, index, day, opts.week_start);
+            let (xf, yf) = (col as f64, row as f64);
+            let corners = [proj.project(xf, yf, 0.0), proj.project(xf + 1.0, yf, 0.0), proj.project(xf + 1.0, yf + 1.0, 0.0), proj.project(xf, yf + 1.0, 0.0)];
+            for (px, py) in corners {
+                min = (min.0.min(px), min.1.min(py));
+                max = (max.0.max(px), max.1.max(py));
+            }
+            let color = cell_color(x, day.contribution_count, &scale, opts);
+            g = g.add(Polygon::new().set("points", points(&corners)).set("fill", color.as_str()).set("stroke", opts.theme.background.as_str()).set("stroke-width", stroke_width(1.0, opts)));
+        }
+    }
+    let side = if min.0 > max.0 { 10.0 } else { (max.0 - min.0).max(max.1 - min.1) + 10.0 };
+    let center = if min.0 > max.0 { (0.0, 0.0) } else { ((min.0 + max.0) / 2.0, (min.1 + max.1) / 2.0) };
+    Document::new()
+        .set("viewBox", (center.0 - side / 2.0, center.1 - side / 2.0, side, side))
+        .set("width", MINI_SIZE)
+        .set("height", MINI_SIZE)
+        .set("style", format!("background:{};", opts.theme.background))
+        .add(g)
+}
+
+const LANGUAGE_CARD_PADDING: f64 = 24.0;
+const LANGUAGE_CARD_TITLE: f64 = 40.0;
+
+// Canvas extent of the donut ring and its legend for `langs`.
+fn donut_bounds(langs: &[LanguageStat], opts: &Options) -> ((f64, f64), (f64, f64)) {
+    let panels = PanelLayout::new(opts);
+    let (cx, cy) = panels.donut_center;
+    let labels = legend_labels(&donut_slices(langs, opts), opts);
+    let legend = LegendLayout::new(&labels, opts.legend_cols, &panels);
+    let (legend_w, legend_h) = legend.extent(labels.len());
+    let min = (cx + (-DONUT_RADIUS).min(legend.x), cy + (-DONUT_RADIUS).min(LEGEND_Y));
+    let max = (cx + DONUT_RADIUS.max(legend.x + legend_w), cy + DONUT_RADIUS.max(LEGEND_Y + legend_h));
+    (min, max)
+}
+
+// --only donut: the donut and legend as drawn on the full card, with the
+// viewBox cropped to their extent plus a title above and totals below.
+fn render_language_card(langs: &[LanguageStat], repos: usize, opts: &Options) -> Document {
+    let ((left, top), (right, bottom)) = donut_bounds(langs, opts);
+    let x = left - LANGUAGE_CARD_PADDING;
+    let y = top - LANGUAGE_CARD_PADDING - LANGUAGE_CARD_TITLE;
+    let width = right - left + 2.0 * LANGUAGE_CARD_PADDING;
+    let height = bottom - top + 2.0 * (LANGUAGE_CARD_PADDING + LANGUAGE_CARD_TITLE);
+
+    let mut doc = Document::new().set("viewBox", (x, y, width, height)).set("style", escape_attr(&format!("background:{}; font-family: {};", opts.theme.background, opts.font_family_css())));
+    if let Some((w, h)) = opts.size {
+        doc = doc.set("width", w).set("height", h);
+    }
+    let totals = format!("{} languages across {} repositories", opts.locale.format_int(langs.len() as i64), opts.locale.format_int(repos as i64));
+    doc.add(SvgText::new().set("x", x + LANGUAGE_CARD_PADDING).set("y", y + LANGUAGE_CARD_PADDING + 16.0).set("fill", opts.theme.text.as_str()).set("font-size", 20).set("font-weight", "bold").add(TextNode::new("Most used languages")))
+        .add(draw_donut_chart(langs, opts))
+        .add(SvgText::new().set("x", x + LANGUAGE_CARD_PADDING).set("y", y + height - LANGUAGE_CARD_PADDING).set("fill", opts.theme.text.as_str()).set("font-size", 13).add(TextNode::new(totals)))
+}
+
+// Height of the canvas strip holding the footer
+const FOOTER_STRIP: f64 = 60.0;
+
+/// Output widths (in pixels) below which the footer switches to its compact
+/// form on its own, since the full one no longer reads at that scale.
+pub const COMPACT_FOOTER_BELOW: u32 = 700;
+
+fn draw_footer(contributions: i32, stars: i32, forks: i32, opts: &Options) -> SvgText {
+    let compact = opts.compact_footer || opts.size.is_some_and(|(width, _)| width < COMPACT_FOOTER_BELOW);
+    let (text, font_size) = if compact {
+        let fmt = |n: i32| opts.locale.format_compact(n as i64);
+        (format!("{} contrib · ⭐ {} · {}", fmt(contributions), fmt(stars), fmt(forks)), 18)
+    } else {
+        let fmt = |n: i32| opts.locale.format_int(n as i64);
+        (format!("{} contributions    ⭐ {}     {}", fmt(contributions), fmt(stars), fmt(forks)), 24)
+    };
+    SvgText::new().set("x", VIEW_WIDTH / 2.0).set("y", VIEW_HEIGHT - 40.0).set("fill", opts.theme.text.as_str()).set("text-anchor", "middle").set("font-size", font_size).set("font-weight", "bold").add(TextNode::new(text))
+}
+
+fn drawn_languages(user: &User, opts: &Options) -> Vec<LanguageStat> {
+    language_breakdown(match opts.lang_weight {
+        LangWeight::Bytes => aggregate_languages(&user.repositories.nodes),
+        LangWeight::Repos => aggregate_primary_languages(&user.repositories.nodes),
+    })
+}
+
+/// Renders the full profile card for an already-fetched user.
+pub fn render_profile(user: &User, opts: &Options) -> Document {
+    let mut doc = Document::new();
+    draw_profile(user, opts, &mut |part| match part {
+        CardPart::Root(root) => doc = root,
+        CardPart::Node(node) => doc.append(node),
+    });
+    doc
+}
+
+/// A piece of a card as `draw_profile` hands it out, in document order.
+pub enum CardPart {
+    /// The root `<svg>` and its attributes, viewBox included; always first.
+    /// The `--mini` and `--only` cards come whole, as this alone.
+    Root(Document),
+    /// A top-level element of the full card, in paint order.
+    Node(Box<dyn Node>),
+}
+
+/// Draws the card `render_profile` returns, passing each panel to `emit` as
+/// soon as it's drawn, so a caller can write the card out without holding
+/// all of it (see `output::SvgStream`).
+pub fn draw_profile(user: &User, opts: &Options, emit: &mut dyn FnMut(CardPart)) {
+    if opts.mini {
+        return emit(CardPart::Root(render_mini(&user.contributions_collection.contribution_calendar.weeks, opts)));
+    }
+    let langs = drawn_languages(user, opts);
+    if opts.only == Some(OnlyPanel::Donut) {
+        return emit(CardPart::Root(render_language_card(&langs, user.repositories.nodes.len(), opts)));
+    }
+    let total_stars: i32 = user.repositories.nodes.iter().map(|r| r.stargazer_count).sum();
+    let total_forks: i32 = user.repositories.nodes.iter().map(|r| r.fork_count).sum();
+
+    with_drawn_calendar(user, opts, |weeks, opts, adj| {
+        // The canvas is settled before anything is drawn, so the root can go first
+        let mut root = Document::new().set("style", escape_attr(&format!("background:{}; font-family: {};", opts.theme.background, opts.font_family_css()))).set("viewBox", (0.0, 0.0, adj.canvas.0, adj.canvas.1));
+        if let Some((width, height)) = opts.size {
+            root = root.set("width", width).set("height", height);
+        }
+        emit(CardPart::Root(root));
+        let mut add = |node: Box<dyn Node>| emit(CardPart::Node(node));
+        if let Some(defs) = build_defs(opts) {
+            add(defs.into());
+        }
+        if opts.embed_data {
+            add(data_island(user).into());
+        }
+        if let Some(background) = draw_background(opts) {
+            add(background.into());
+        }
+
+        let mut heatmap = match (opts.layout, opts.style) {
+            _ if !adj.has_days => draw_empty_heatmap(opts),
+            (HeatmapLayout::Rings, _) => draw_year_rings(weeks, opts),
+            (HeatmapLayout::Grid, _) if opts.granularity == Granularity::Week => draw_3d_heatmap(weeks, opts),
+            (HeatmapLayout::Grid, Style::Bars | Style::Sparkline) => draw_3d_heatmap(weeks, opts),
+            (HeatmapLayout::Grid, Style::Surface) => draw_3d_surface(weeks, opts),
+            (HeatmapLayout::Grid, Style::Flat) => draw_flat_heatmap(weeks, opts),
+        };
+        if let Some(filter) = bar_filter(opts) {
+            heatmap = heatmap.set("filter", filter);
+        }
+        if adj.has_days && opts.layout == HeatmapLayout::Grid {
+            add(draw_week_ticks(weeks, opts).into());
+            if opts.year_separators && opts.granularity == Granularity::Day {
+                add(draw_year_separators(weeks, opts).into());
+            }
+        }
+        add(heatmap.into());
+        if opts.layout == HeatmapLayout::Grid && opts.granularity == Granularity::Day {
+            add(draw_axis_labels(weeks, opts).into());
+        }
+        if !opts.annotations.is_empty() && opts.layout == HeatmapLayout::Grid && opts.granularity == Granularity::Day {
+            add(draw_annotations(weeks, opts).into());
+        }
+        if let Some(goal) = opts.goal
+            && adj.has_days
+            && opts.layout == HeatmapLayout::Grid
+            && opts.style == Style::Bars
+            && opts.granularity == Granularity::Day
+        {
+            add(draw_goal_plane(weeks, goal, opts).into());
+        }
+        if opts.top_days > 0 && adj.has_days && opts.granularity == Granularity::Day {
+            add(draw_top_days(weeks, opts).into());
+        }
+        if opts.show_name || opts.show_avatar {
+            add(draw_profile_header(user, opts).into());
+        }
+        if opts.legend && adj.has_days && opts.layout == HeatmapLayout::Grid && opts.style != Style::Flat {
+            add(draw_height_legend(weeks, opts).into());
+        }
+        if opts.panels.donut {
+            add(draw_donut_chart(&langs, opts).into());
+            if let (true, Some(goal)) = (opts.goal_ring, opts.goal) {
+                add(draw_goal_ring(&user.contributions_collection.contribution_calendar.weeks, goal, opts).into());
+            }
+        }
+        if opts.panels.repo_langs {
+            add(draw_repo_languages(&repo_languages(&user.repositories.nodes, MAX_REPO_LANGS), opts).into());
+        }
+        if opts.panels.radar {
+            add(draw_radar_chart(&[user.contributions_collection.total_commit_contributions, user.contributions_collection.total_issue_contributions, user.contributions_collection.total_pull_request_contributions, user.contributions_collection.total_pull_request_review_contributions, user.contributions_collection.total_repository_contributions], opts).into());
+        }
+
+        // Footer - Placed at safe bottom
+        if opts.panels.footer {
+            add(draw_footer(user.contributions_collection.contribution_calendar.total_contributions, total_stars, total_forks, opts).into());
+        }
+
+        let notes = footnotes(weeks, opts);
+        if !notes.is_empty() {
+            add(SvgText::new().set("x", VIEW_WIDTH / 2.0).set("y", VIEW_HEIGHT - 16.0).set("fill", opts.theme.text.as_str()).set("text-anchor", "middle").set("font-size", 12).add(TextNode::new(notes.join(" · "))).into());
+        }
+
+        if opts.interactive && opts.style == Style::Bars && opts.layout == HeatmapLayout::Grid {
+            let (tip, script) = draw_hover_layer(opts);
+            add(tip.into());
+            add(script.into());
+        }
+    })
+}
+
+/// `--explain`: the full card's layout as render_profile would compute it,
+/// one fact per line, for debugging placement without opening the SVG.
+/// Boxes are `x0,y0 .. x1,y1` in canvas coordinates.
+pub fn explain_layout(user: &User, opts: &Options) -> String {
+    if opts.mini || opts.only.is_some() {
+        return "--mini and --only cards use their own fixed layout; nothing to explain".to_string();
+    }
+    let langs = drawn_languages(user, opts);
+    with_drawn_calendar(user, opts, |weeks, opts, adj| {
+        let boxed = |((x0, y0), (x1, y1)): ((f64, f64), (f64, f64))| format!("{:.1},{:.1} .. {:.1},{:.1}", x0, y0, x1, y1);
+        let p = &opts.projection;
+        let mut lines = vec![
+            format!("canvas: viewBox 0 0 {:.1} {:.1}", adj.canvas.0, adj.canvas.1),
+            format!("projection: origin {:.1},{:.1}, angle {}°, scale {:.2}{}", p.origin_x, p.origin_y, p.angle_deg, p.scale, if p.mirrored { ", mirrored" } else { "" }),
+        ];
+        if let Some(scale) = adj.fitted_from {
+            lines.push(format!("fit: scale shrunk from {:.2} so {} columns fit the canvas", scale, weeks.len()));
+        }
+        if adj.shift != (0.0, 0.0) {
+            lines.push(format!("shift: grid moved {:.1} right and {:.1} down to stay on the canvas", adj.shift.0, adj.shift.1));
+        }
+        if opts.trim_empty_edges {
+            lines.push(format!("trim: dropped {} leading and {} trailing empty weeks", adj.trimmed.0, adj.trimmed.1));
+        }
+        if let Some(cap) = opts.clamp_count {
+            lines.push(format!("clamp: {} days capped at {} contributions", clamped_days(weeks, opts), cap));
+        }
+        match (opts.layout, projected_bounds(weeks, opts)) {
+            (HeatmapLayout::Grid, Some(bounds)) => lines.push(format!("heatmap: {} weeks, {}", weeks.len(), boxed(bounds))),
+            (HeatmapLayout::Grid, None) => lines.push("heatmap: empty placeholder".to_string()),
+            (HeatmapLayout::Rings, _) => lines.push(format!("heatmap: {} year rings", weeks.len().div_ceil(53))),
+        }
+        let panels = PanelLayout::new(opts);
+        if opts.panels.donut {
+            lines.push(format!("donut: {} languages, {}", langs.len(), boxed(donut_bounds(&langs, opts))));
+        }
+        if opts.panels.radar {
+            let (cx, cy) = panels.radar_center;
+            let r = RADAR_RADIUS + RADAR_LABEL_MARGIN;
+            lines.push(format!("radar: {}", boxed(((cx - r, cy - r), (cx + r, cy + r)))));
+        }
+        if opts.panels.repo_langs {
+            let count = repo_languages(&user.repositories.nodes, MAX_REPO_LANGS).len();
+            let x = if opts.projection.mirrored { VIEW_WIDTH - GRID_MARGIN - REPO_LANGS_WIDTH } else { GRID_MARGIN };
+            lines.push(format!("repo-langs: {} repositories, {}", count, boxed(((x, REPO_LANGS_Y - 15.0), (x + REPO_LANGS_WIDTH, REPO_LANGS_Y + 24.0 + count as f64 * REPO_LANGS_ROW)))));
+        }
+        if opts.panels.footer {
+            lines.push(format!("footer: {}", boxed(((0.0, VIEW_HEIGHT - FOOTER_STRIP), (VIEW_WIDTH, VIEW_HEIGHT)))));
+        }
+        lines.join("\n")
+    })
+}
+
+// What render_profile changes before drawing: weeks dropped by
+// --trim-empty-edges, the scale the fit replaced, the shift that keeps the
+// grid on the canvas, and the resulting canvas size.
+struct Adjustments {
+    has_days: bool,
+    trimmed: (usize, usize),
+    fitted_from: Option<f64>,
+    shift: (f64, f64),
+    canvas: (f64, f64),
+}
+
+// Trims and aggregates the calendar and fits and shifts the projection, then
+// hands the weeks and options as drawn to `draw`.
+fn with_drawn_calendar<R>(user: &User, opts: &Options, draw: impl FnOnce(&[Week], &Options, &Adjustments) -> R) -> R {
+    let weeks = &user.contributions_collection.contribution_calendar.weeks;
+    let has_days = weeks.iter().any(|w| !w.contribution_days.is_empty());
+    let trimmed = if opts.trim_empty_edges { empty_edge_weeks(weeks) } else { (0, 0) };
+    let weeks = &weeks[trimmed.0..weeks.len() - trimmed.1];
+    let weekly;
+    let weeks = match (opts.granularity, opts.layout) {
+        (Granularity::Week, HeatmapLayout::Grid) => {
+            weekly = weekly_totals(weeks);
+            &weekly
+        }
+        _ => weeks,
+    };
+    let normalized;
+    let opts = if opts.height_mode == HeightMode::Normalized {
+        normalized = Options { height_max: max_drawn_count(weeks, opts), ..opts.clone() };
+        &normalized
+    } else {
+        opts
+    };
+    let fitted;
+    let (opts, fitted_from) = match fitted_projection(weeks, opts) {
+        Some(projection) => {
+            fitted = Options { projection, ..opts.clone() };
+            (&fitted, Some(opts.projection.scale))
+        }
+        None => (opts, None),
+    };
+    let shift = content_shift(weeks, opts);
+    let shifted;
+    let opts = if shift.0 > 0.0 || shift.1 > 0.0 {
+        shifted = Options { projection: shifted_projection(&opts.projection, shift.0, shift.1), ..opts.clone() };
+        &shifted
+    } else {
+        opts
+    };
+    // Without the footer (or the notes below it) the bottom strip is empty
+    let height = if opts.panels.footer || !footnotes(weeks, opts).is_empty() { VIEW_HEIGHT } else { VIEW_HEIGHT - FOOTER_STRIP };
+    let canvas = (VIEW_WIDTH + shift.0, height + shift.1);
+    draw(weeks, opts, &Adjustments { has_days, trimmed, fitted_from, shift, canvas })
+}
+
+// Small print under the footer about how the card departs from the data.
+fn footnotes(weeks: &[Week], opts: &Options) -> Vec<String> {
+    let mut notes = Vec::new();
+    if let (true, Some(cap)) = (clamped_days(weeks, opts) > 0, opts.clamp_count) {
+        notes.push(format!("Bar heights capped at {} contributions", cap));
+    }
+    if opts.source == Source::Rest {
+        notes.push("Approximate: recent public events only".to_string());
+    }
+    notes
+}
+
+fn clamped_days(weeks: &[Week], opts: &Options) -> usize {
+    weeks.iter().flat_map(|w| &w.contribution_days).filter(|d| is_clamped(d.contribution_count, opts)).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::HeightCurve;
+    use crate::theme::Theme;
+
+    fn assert_close(actual: (f64, f64), expected: (f64, f64)) {
+        assert!((actual.0 - expected.0).abs() < 1e-9 && (actual.1 - expected.1).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+    }
+
+    fn counts_week(counts: impl IntoIterator<Item = i32>) -> Vec<Week> {
+        vec![Week { contribution_days: counts.into_iter().map(|c| Day { contribution_count: c, date: None }).collect() }]
+    }
+
+    #[test]
+    fn surface_splits_each_cell_between_four_days_into_two_triangles() {
+        let weeks: Vec<Week> = (0..3).flat_map(|w| counts_week((0..7).map(move |d| w * 7 + d))).collect();
+        let svg = draw_3d_surface(&weeks, &Options::default()).to_string();
+        // 2 gaps between weeks x 6 gaps between days, two triangles each
+        assert_eq!(svg.matches("<polygon").count(), 24);
+        for points in svg.split("points=\"").skip(1).map(|rest| rest.split('"').next().unwrap()) {
+            assert!(points.split([' ', ',']).all(|v| v.parse::<f64>().is_ok_and(f64::is_finite)), "{}", points);
+        }
+    }
+
+    #[test]
+    fn surface_follows_the_week_start() {
+        // Three weeks from Sunday 2025-01-05
+        let start = Date::new(2025, 1, 5).unwrap();
+        let weeks: Vec<Week> = (0..3)
+            .map(|w| Week { contribution_days: (0..7).map(|d| Day { contribution_count: 1, date: Some(start.add_days(w * 7 + d)) }).collect() })
+            .collect();
+        let sunday = draw_3d_surface(&weeks, &Options::default()).to_string();
+        let monday = draw_3d_surface(&weeks, &Options { week_start: WeekStart::Monday, ..Options::default() }).to_string();
+        assert_eq!(sunday.matches("<polygon").count(), 24);
+        // The first Sunday sits alone in column 0 and the last week's Monday to
+        // Saturday spill into column 3, leaving 6 + 5 quads
+        assert_eq!(monday.matches("<polygon").count(), 22);
+    }
+
+    #[test]
+    fn intensity_levels_split_nonzero_counts_into_quantiles() {
+        let weeks = counts_week([0, 0].into_iter().chain(1..=12));
+        let three = intensity_thresholds(&weeks, 3);
+        assert_eq!(three, [7]);
+        assert_eq!([0, 1, 6, 7, 12].map(|c| intensity_level(c, &three)), [0, 1, 1, 2, 2]);
+
+        let seven = intensity_thresholds(&weeks, 7);
+        assert_eq!(seven, [3, 5, 7, 9, 11]);
+        assert_eq!([1, 2, 3, 8, 12].map(|c| intensity_level(c, &seven)), [1, 1, 2, 4, 6]);
+
+        let dark = Options { color_mode: ColorMode::Intensity, theme: Theme::dark(), ..Options::default() };
+        let scale = ColorScale::new(&weeks, &dark);
+        assert_eq!(cell_color(0, 0, &scale, &dark), dark.theme.empty_cell);
+        assert_eq!(intensity_color(1, 7, false), INTENSITY_RAMP[0]);
+        assert_eq!(intensity_color(6, 7, false), INTENSITY_RAMP[3]);
+        assert_eq!(intensity_color(6, 7, true), CVD_INTENSITY_RAMP[3]);
+        assert!(intensity_thresholds(&counts_week([0, 0]), 5).is_empty());
+    }
+
+    #[test]
+    fn uniform_counts_share_one_intensity_level() {
+        let weeks = counts_week([0, 4, 4, 4, 4]);
+        let thresholds = intensity_thresholds(&weeks, 5);
+        assert_eq!(thresholds, [4, 4, 4]);
+        assert_eq!([0, 4].map(|c| intensity_level(c, &thresholds)), [0, 4]);
+        let opts = Options { color_mode: ColorMode::Intensity, ..Options::default() };
+        assert_eq!(cell_color(0, 4, &ColorScale::new(&weeks, &opts), &opts), INTENSITY_RAMP[3]);
+    }
+
+    #[test]
+    fn trimming_drops_empty_leading_weeks() {
+        let mut user = crate::demo::demo_user();
+        for week in &mut user.contributions_collection.contribution_calendar.weeks[..10] {
+            week.contribution_days.iter_mut().for_each(|d| d.contribution_count = 0);
+        }
+        // Three faces per bar, ten weeks of seven days
+        let faces = |opts: &Options| render_profile(&user, opts).to_string().matches("<polygon").count();
+        assert_eq!(faces(&Options::default()) - faces(&Options { trim_empty_edges: true, ..Options::default() }), 3 * 70);
+    }
+
+    #[test]
+    fn out_of_canvas_projections_are_shifted_inside() {
+        let weeks = &crate::demo::demo_user().contributions_collection.contribution_calendar.weeks;
+        for mirrored in [false, true] {
+            // Mirrored, an origin at the right edge puts the grid past the left one
+            let origin_x = if mirrored { VIEW_WIDTH } else { 0.0 };
+            let opts = Options { projection: Projection { origin_x, origin_y: 0.0, angle_deg: 30.0, scale: 20.0, mirrored }, ..Options::default() };
+            let (dx, dy) = content_shift(weeks, &opts);
+            assert!(dx > 0.0 && dy > 0.0);
+            let moved = Options { projection: shifted_projection(&opts.projection, dx, dy), ..opts };
+            let ((min_x, min_y), _) = projected_bounds(weeks, &moved).unwrap();
+            assert!(min_x >= GRID_MARGIN - 1e-9 && min_y >= GRID_MARGIN - 1e-9);
+            let bars = draw_3d_heatmap(weeks, &moved).to_string();
+            let points = bars.split("points=\"").skip(1).map(|p| p.split('"').next().unwrap());
+            assert!(points.flat_map(|p| p.split([' ', ','])).filter(|v| !v.is_empty()).all(|v| !v.starts_with('-')));
+        }
+        assert_eq!(content_shift(weeks, &Options::default()), (0.0, 0.0));
+    }
+
+    #[test]
+    fn top_days_panel_lists_the_requested_count() {
+        let svg = render_profile(&crate::demo::demo_user(), &Options { top_days: 3, ..Options::default() }).to_string();
+        assert!(svg.contains("Busiest days"));
+        assert!(svg.contains("3. ") && !svg.contains("4. "));
+    }
+
+    #[test]
+    fn no_footer_drops_the_text_and_its_strip() {
+        let user = crate::demo::demo_user();
+        let svg = render_profile(&user, &Options::default().without_footer()).to_string();
+        assert!(!svg.contains("contributions    ⭐"));
+        assert!(svg.contains(&format!("viewBox=\"0 0 {} {}\"", VIEW_WIDTH, VIEW_HEIGHT - FOOTER_STRIP)));
+        assert!(render_profile(&user, &Options::default()).to_string().contains(&format!("viewBox=\"0 0 {} {}\"", VIEW_WIDTH, VIEW_HEIGHT)));
+    }
+
+    fn stat(name: &str, bytes: i64, percent: f64) -> LanguageStat {
+        LanguageStat { name: name.to_string(), bytes, percent, color: "#dea584".to_string() }
+    }
+
+    #[test]
+    fn legend_shares_add_up_and_tiny_languages_become_other() {
+        let langs = [stat("Rust", 3000, 50.06), stat("Go", 1800, 30.0), stat("C", 1180, 19.74), stat("Shell", 10, 0.1), stat("Make", 10, 0.1)];
+        let slices = donut_slices(&langs, &Options::default());
+        assert_eq!(slices.iter().map(|l| l.name.as_str()).collect::<Vec<_>>(), ["Rust", "Go", "C", "Other"]);
+        assert_eq!(slices[3].bytes, 20);
+
+        let tenths = rounded_percents(&[33.34, 33.33, 33.33]);
+        assert_eq!(tenths.iter().sum::<u32>(), 1000);
+        assert_eq!(tenths, [334, 333, 333]);
+
+        let labels = legend_labels(&slices, &Options { legend_sizes: true, ..Options::default() });
+        assert_eq!(labels[0], "Rust 50.1% · 2.9 KB");
+        assert_eq!(labels[3], "Other 0.2% · 20 B");
+        assert_eq!(donut_slices(&langs, &Options { other_below: 0.0, ..Options::default() }).len(), 5);
+    }
+
+    #[test]
+    fn legend_cols_spreads_entries_over_columns_on_the_canvas() {
+        let labels = vec!["Rust 10.0%".to_string(); 12];
+        let default = Options::default();
+        let fixed = LegendLayout::new(&labels, default.legend_cols, &PanelLayout::new(&default));
+        assert_eq!(labels.len().div_ceil(fixed.rows), 2);
+
+        for cols in ["1", "3", "6"] {
+            let opts = crate::options::parse_args(["--legend-cols", cols].iter().map(|s| s.to_string())).unwrap();
+            let panels = PanelLayout::new(&opts);
+            let legend = LegendLayout::new(&labels, opts.legend_cols, &panels);
+            assert_eq!(labels.len().div_ceil(legend.rows).to_string(), cols);
+            let (cx, cy) = panels.donut_center;
+            let (width, height) = legend.extent(labels.len());
+            assert!(cx + legend.x >= 0.0 && cx + legend.x + width <= VIEW_WIDTH, "{} columns overflow", cols);
+            assert!(cy + LEGEND_Y + height <= VIEW_HEIGHT, "{} columns overflow", cols);
+        }
+    }
+
+    #[test]
+    fn legend_columns_fit_their_labels() {
+        let panels = PanelLayout::new(&Options::default());
+        let labels = vec!["JavaScript 45.2% · 123.4 MB".to_string(); 8];
+        let roomy = LegendLayout::new(&labels, None, &panels);
+        assert!(roomy.col_width > LEGEND_COL_WIDTH);
+        assert_eq!(roomy.font_size, LEGEND_FONT_SIZE);
+
+        let squeezed = LegendLayout::new(&labels, Some(8), &panels);
+        assert!(squeezed.extent(8).0 <= panels.legend_max_width());
+        assert!(squeezed.font_size < LEGEND_FONT_SIZE);
+    }
+
+    #[test]
+    fn mirrored_orientation_reflects_the_grid_and_panels() {
+        let mirrored = Options { projection: Projection { mirrored: true, ..Projection::default() }, ..Options::default() };
+        let (sx, sy) = Projection::default().project(10.0, 3.0, 5.0);
+        assert_close(mirrored.projection.project(10.0, 3.0, 5.0), (VIEW_WIDTH - sx, sy));
+
+        let panels = PanelLayout::new(&mirrored);
+        assert_eq!(panels.donut_center.0, VIEW_WIDTH - PanelLayout::new(&Options::default()).donut_center.0);
+        let legend = LegendLayout::new(&vec!["Rust 20.0%".to_string(); 5], None, &panels);
+        assert!(legend.position(0).0 + legend.extent(5).0 <= -DONUT_RADIUS);
+    }
+
+    #[test]
+    fn language_card_crops_to_the_donut_and_legend() {
+        let svg = render_profile(&crate::demo::demo_user(), &Options { only: Some(OnlyPanel::Donut), ..Options::default() }).to_string();
+        assert!(svg.contains("Most used languages") && svg.contains("across 6 repositories"));
+        assert!(!svg.contains("hm-bar") && !svg.contains("Commit"));
+        let view_box: Vec<f64> = svg.split("viewBox=\"").nth(1).unwrap().split('"').next().unwrap().split_whitespace().map(|v| v.parse().unwrap()).collect();
+        assert!(view_box[2] < VIEW_WIDTH && view_box[3] < VIEW_HEIGHT);
+    }
+
+    #[test]
+    fn user_strings_are_escaped_in_the_document() {
+        let mut user = crate::demo::demo_user();
+        let edges = &mut user.repositories.nodes[0].languages.as_mut().unwrap().edges;
+        edges[0].node.name = "R&D <script>".to_string();
+        edges[1].node.name = "日本語".to_string();
+        let opts = Options { font_family: "Tom & Jerry's".to_string(), other_below: 0.0, ..Options::default() };
+        let svg = render_profile(&user, &opts).to_string();
+        assert!(svg.contains("R&amp;D &lt;script&gt;") && !svg.contains("<script>"));
+        assert!(svg.contains("日本語"));
+        assert!(svg.contains("Tom &amp; Jerry&apos;s"));
+    }
+
+    #[test]
+    fn narrow_canvases_get_the_compact_footer() {
+        let full = draw_footer(12_345, 80, 3, &Options::default()).to_string();
+        assert!(full.contains("12,345 contributions"));
+        let narrow = draw_footer(12_345, 80, 3, &Options { size: Some((400, 300)), ..Options::default() }).to_string();
+        assert!(narrow.contains("12.3k contrib") && narrow.contains("font-size=\"18\""));
+        assert_eq!(draw_footer(12_345, 80, 3, &Options { compact_footer: true, ..Options::default() }).to_string(), narrow);
+    }
+
+    #[test]
+    fn flat_cells_are_rounded_rects_on_the_ground_plane() {
+        let svg = render_profile(&crate::demo::demo_user(), &Options { style: Style::Flat, cell_radius: 0.4, ..Options::default() }).to_string();
+        assert!(svg.contains("matrix("));
+        assert!(svg.contains(&format!("rx=\"{}\"", 0.4 * FLAT_CELL_SIZE)));
+    }
+
+    #[test]
+    fn secondary_series_drives_bar_fill() {
+        let date = Date::parse("2024-05-01");
+        let opts = Options { color_series: Some(BTreeMap::from([(date.unwrap(), 4), (Date::parse("2024-05-02").unwrap(), 2)])), ..Options::default() };
+        let busy = Day { contribution_count: 1, date };
+        let quiet = Day { contribution_count: 40, date: Date::parse("2024-05-09") };
+        assert_eq!(secondary_color(&busy, &opts).as_deref(), Some(DEFAULT_GRADIENT[1]));
+        assert_eq!(secondary_color(&quiet, &opts).as_deref(), Some(opts.theme.empty_cell.as_str()));
+        assert_eq!(secondary_color(&busy, &Options::default()), None);
+
+        // The default ramp starts from the theme's empty cell
+        let dark = Options { theme: Theme::dark(), ..opts };
+        assert_eq!(secondary_color(&quiet, &dark).as_deref(), Some(dark.theme.empty_cell.as_str()));
+    }
+
+    #[test]
+    fn cvd_safe_swaps_seasonal_and_donut_palettes() {
+        let opts = Options { cvd_safe: true, ..Options::default() };
+        let scale = ColorScale { max: 10, thresholds: Vec::new(), recent_from: None };
+        assert_eq!(cell_color(0, 3, &scale, &opts), CVD_QUALITATIVE[0]);
+        assert_eq!(cell_color(51, 3, &scale, &opts), CVD_QUALITATIVE[3]);
+        assert_eq!(cell_color(51, 0, &scale, &opts), opts.theme.empty_cell);
+
+        let user = crate::demo::demo_user();
+        let svg = render_profile(&user, &opts).to_string();
+        let first = &user.repositories.nodes[0].languages.as_ref().unwrap().edges[0].node;
+        assert!(svg.contains(&format!(r#"fill="{}""#, CVD_QUALITATIVE[0])));
+        assert!(first.color.as_ref().is_none_or(|c| !svg.contains(&format!(r#"fill="{}""#, c))));
+    }
+
+    #[test]
+    fn profile_header_falls_back_to_the_login() {
+        let mut user = crate::demo::demo_user();
+        let opts = Options { show_name: true, ..Options::default() };
+        assert!(draw_profile_header(&user, &opts).to_string().contains("The Octocat"));
+        user.name = None;
+        let header = draw_profile_header(&user, &opts).to_string();
+        assert!(header.contains("octocat") && !header.contains("Octocat"));
+
+        user.avatar_url = Some("https://avatars.example/u/1?s=80&v=4".to_string());
+        let opts = Options { show_avatar: true, ..Options::default() };
+        let header = draw_profile_header(&user, &opts).to_string();
+        assert!(header.contains("s=80&amp;v=4") && !header.contains("octocat"));
+        let embedded = Options { avatar: Some("data:image/png;base64,AAAA".to_string()), ..opts.clone() };
+        assert!(draw_profile_header(&user, &embedded).to_string().contains("data:image/png;base64,AAAA"));
+        assert!(build_defs(&opts).unwrap().to_string().contains(AVATAR_CLIP_ID));
+    }
+
+    #[test]
+    fn goal_recolors_met_days_and_draws_a_plane() {
+        let opts = Options { goal: Some(3), ..Options::default() };
+        let scale = ColorScale { max: 10, thresholds: Vec::new(), recent_from: None };
+        assert_eq!(day_fill(0, &Day { contribution_count: 3, date: None }, &scale, &opts), GOAL_MET);
+        assert_ne!(day_fill(0, &Day { contribution_count: 2, date: None }, &scale, &opts), GOAL_MET);
+        let weekly = Options { granularity: Granularity::Week, ..opts.clone() };
+        assert_ne!(day_fill(0, &Day { contribution_count: 30, date: None }, &scale, &weekly), GOAL_MET);
+
+        let weeks = &crate::demo::demo_user().contributions_collection.contribution_calendar.weeks;
+        let plane = draw_goal_plane(weeks, 3, &opts).to_string();
+        let (x, y) = opts.projection.project(0.0, 0.0, cell_height(3, &opts));
+        assert!(plane.contains(&format!("{},{} ", x, y)) && plane.contains("fill-opacity"));
+    }
+
+    #[test]
+    fn explain_reports_the_adjustments_render_applies() {
+        let mut user = crate::demo::demo_user();
+        for week in &mut user.contributions_collection.contribution_calendar.weeks[..2] {
+            week.contribution_days.iter_mut().for_each(|d| d.contribution_count = 0);
+        }
+        let opts = Options { trim_empty_edges: true, clamp_count: Some(10), ..Options::default() };
+        let report = explain_layout(&user, &opts);
+        let view_box = render_profile(&user, &opts).to_string().split_once("viewBox=\"").unwrap().1.split('"').next().unwrap().to_string();
+        let view_box: Vec<f64> = view_box.split(' ').map(|v| v.parse().unwrap()).collect();
+        assert!(report.contains(&format!("viewBox 0 0 {:.1} {:.1}", view_box[2], view_box[3])), "{}", report);
+        assert!(report.contains("trim: dropped 2 leading"));
+        assert!(report.contains("clamp: ") && report.contains("heatmap: 51 weeks") && report.contains("donut: "));
+    }
+
+    #[test]
+    fn recent_window_outlines_new_days_and_dims_the_rest() {
+        let weeks = &crate::demo::demo_user().contributions_collection.contribution_calendar.weeks;
+        let last = weeks.iter().flat_map(|w| &w.contribution_days).filter_map(|d| d.date).max().unwrap();
+        let opts = Options { highlight_recent: Some(7), ..Options::default() };
+        let scale = ColorScale::new(weeks, &opts);
+        let day = |offset: i64| Day { contribution_count: 4, date: Some(last.add_days(offset)) };
+        assert!(scale.is_recent(&day(0)) && scale.is_recent(&day(-6)) && !scale.is_recent(&day(-7)));
+        assert_eq!(draw_3d_heatmap(weeks, &opts).to_string().matches(&format!(r#"stroke="{}""#, opts.theme.accent)).count(), 7);
+
+        // Dimming is separate: off by default, and a no-op without a window
+        assert_eq!(day_fill(0, &day(-30), &scale, &opts), base_fill(0, &day(-30), &scale, &opts));
+        let dimmed = Options { dim_older: true, ..opts.clone() };
+        assert_ne!(day_fill(0, &day(-30), &scale, &dimmed), base_fill(0, &day(-30), &scale, &dimmed));
+        assert_eq!(day_fill(0, &day(0), &scale, &dimmed), base_fill(0, &day(0), &scale, &dimmed));
+        let no_window = Options { dim_older: true, ..Options::default() };
+        let plain = ColorScale::new(weeks, &no_window);
+        assert_eq!(day_fill(0, &day(-30), &plain, &no_window), base_fill(0, &day(-30), &plain, &no_window));
+    }
+
+    #[test]
+    fn line_scale_multiplies_strokes_or_offsets_the_output_size() {
+        assert_eq!(stroke_width(1.5, &Options::default()), 1.5);
+        let doubled = Options { line_scale: LineScale::Factor(2.0), ..Options::default() };
+        assert_eq!(stroke_width(1.5, &doubled), 3.0);
+        let small = Options { line_scale: LineScale::Auto, size: Some((350, 250)), ..Options::default() };
+        assert_eq!(stroke_width(1.0, &small), 4.0);
+        // Drawn larger than the canvas, lines keep their width
+        assert_eq!(stroke_width(1.0, &Options { size: Some((2800, 2000)), ..small.clone() }), 1.0);
+        assert!(draw_radar_chart(&[1, 1, 1, 1, 1], &doubled).to_string().contains(r#"stroke-width="4""#));
+    }
+
+    #[test]
+    fn rest_source_cards_are_marked_approximate() {
+        let user = crate::demo::demo_user();
+        let opts = Options { source: Source::Rest, panels: crate::options::Panels { footer: false, ..Default::default() }, ..Options::default() };
+        let svg = render_profile(&user, &opts).to_string();
+        assert!(svg.contains("Approximate: recent public events only"));
+        // The note keeps the strip it sits in
+        assert!(svg.contains(&format!(r#"viewBox="0 0 {} {}""#, VIEW_WIDTH, VIEW_HEIGHT)));
+        assert!(!render_profile(&user, &Options::default()).to_string().contains("Approximate"));
+    }
+
+    #[test]
+    fn minimum_slice_angle_borrows_from_large_slices() {
+        let angles = slice_angles(&[90.0, 9.5, 0.5], Some(10.0));
+        let total: f64 = angles.iter().sum();
+        assert!((total - 2.0 * PI).abs() < 1e-9);
+        assert!((angles[2] - 10f64.to_radians()).abs() < 1e-9);
+        assert!(angles[0] < 0.9 * 2.0 * PI && angles[1] > 10f64.to_radians());
+
+        assert_eq!(slice_angles(&[50.0, 50.0], None), vec![PI, PI]);
+    }
+
+    #[test]
+    fn projection_pins_origin_and_unit_steps() {
+        let proj = Projection::default();
+        assert_close(proj.project(0.0, 0.0, 0.0), (400.0, 300.0));
+        // cos(30°) * 20 = 17.3205..., sin(30°) * 20 = 10
+        assert_close(proj.project(1.0, 0.0, 0.0), (400.0 + 10.0 * 3.0_f64.sqrt(), 310.0));
+        assert_close(proj.project(0.0, 1.0, 0.0), (400.0 - 10.0 * 3.0_f64.sqrt(), 310.0));
+        assert_close(proj.project(0.0, 0.0, 1.0), (400.0, 299.0));
+    }
+
+    #[test]
+    fn clamped_bars_share_the_same_height() {
+        let opts = Options { clamp_count: Some(10), ..Options::default() };
+        assert_eq!(bar_height(50, &opts), bar_height(300, &opts));
+        assert_eq!(bar_height(10, &opts), bar_height(300, &opts));
+        assert!(bar_height(9, &opts) < bar_height(10, &opts));
+        assert!(is_clamped(300, &opts) && !is_clamped(10, &opts));
+    }
+
+    #[test]
+    fn empty_and_single_contribution_cells_differ() {
+        let opts = Options::default();
+        assert_eq!(cell_height(0, &opts), EMPTY_HEIGHT);
+        assert!(cell_height(1, &opts) > cell_height(0, &opts));
+
+        // Even a tiny scale can't collapse a nonzero day onto the empty tile
+        let opts = Options { min_height: 6.0, clamp_count: Some(1), ..Options::default() };
+        assert_eq!(cell_height(1, &opts), 6.0);
+        assert_eq!(cell_height(0, &opts), EMPTY_HEIGHT);
+    }
+
+    #[test]
+    fn normalized_heights_span_floor_to_ceiling() {
+        let weeks = counts_week([0, 3, 12, 6]);
+        let opts = Options { height_mode: HeightMode::Normalized, ..Options::default() };
+        let opts = Options { height_max: max_drawn_count(&weeks, &opts), ..opts };
+        assert_eq!(cell_height(12, &opts), NORMALIZED_CEILING);
+        assert_eq!(cell_height(6, &opts), NORMALIZED_CEILING / 2.0);
+        assert_eq!(cell_height(0, &opts), EMPTY_HEIGHT);
+    }
+
+    #[test]
+    fn goal_progress_compares_the_total_to_the_daily_goal_per_day() {
+        assert_eq!(goal_progress(&counts_week([2, 0, 4, 2]), 4), 0.5);
+        assert_eq!(goal_progress(&counts_week([10, 10]), 4), 2.5);
+        assert_eq!(goal_progress(&[], 4), 0.0);
+    }
+
+    #[test]
+    fn bars_are_painted_back_to_front() {
+        // Out of calendar order: the short Monday comes before the tall Sunday
+        // behind it, which would otherwise be painted over it.
+        let day = |count: i32, date: &str| Day { contribution_count: count, date: Some(date.parse().unwrap()) };
+        let weeks = vec![Week { contribution_days: vec![day(1, "2024-05-06"), day(40, "2024-05-05")] }];
+        let svg = draw_3d_heatmap(&weeks, &Options { interactive: true, ..Options::default() }).to_string();
+        let (sunday, monday) = (svg.find(r#"data-date="2024-05-05""#).unwrap(), svg.find(r#"data-date="2024-05-06""#).unwrap());
+        assert!(sunday < monday);
+    }
+
+    #[test]
+    fn a_runaway_day_stays_on_the_canvas() {
+        let mut user = crate::demo::demo_user();
+        user.contributions_collection.contribution_calendar.weeks[0].contribution_days[0].contribution_count = 5000;
+        let weeks = &user.contributions_collection.contribution_calendar.weeks;
+        let opts = Options::default();
+        let ((_, min_y), _) = projected_bounds(weeks, &opts).unwrap();
+        assert!(min_y >= 0.0, "bar top at {}", min_y);
+        assert_eq!(content_shift(weeks, &opts), (0.0, 0.0));
+        assert!(is_clamped(5000, &opts));
+
+        // Without the cap the same day needs the canvas to grow
+        let uncapped = Options { bar_max: None, ..Options::default() };
+        assert!(content_shift(weeks, &uncapped).1 > 0.0);
+    }
+
+    #[test]
+    fn height_curves_compress_outliers() {
+        let curved = |height_curve| Options { height_curve, bar_max: None, ..Options::default() };
+        assert_eq!(bar_height(100, &curved(HeightCurve::Linear)), 500.0);
+        assert_eq!(bar_height(100, &curved(HeightCurve::Sqrt)), 50.0);
+        assert_eq!(bar_height(1, &curved(HeightCurve::Log)), DEFAULT_BAR_SCALE);
+        assert!(bar_height(1000, &curved(HeightCurve::Log)) < 60.0);
+    }
+
+    #[test]
+    fn bevel_skips_short_bars() {
+        assert_eq!(bevel_drop(EMPTY_HEIGHT), 0.0);
+        assert_eq!(bevel_drop(4.0), 0.0);
+        assert_eq!(bevel_drop(50.0), BEVEL_MAX_DROP);
+    }
+
+    #[test]
+    fn height_legend_samples_up_to_the_clamped_max() {
+        let weeks: Vec<Week> = serde_json::from_value(serde_json::json!([
+            { "contributionDays": [{ "contributionCount": 0 }, { "contributionCount": 40 }] }
+        ]))
+        .unwrap();
+        assert_eq!(height_samples(&weeks, &Options::default()), vec![10, 20, 30, 40]);
+        assert_eq!(height_samples(&weeks, &Options { clamp_count: Some(2), ..Options::default() }), vec![1, 2]);
+        assert!(height_samples(&weeks[..0], &Options::default()).is_empty());
+    }
+
+    #[test]
+    fn weekly_totals_collapse_each_week_into_one_cell() {
+        let weeks: Vec<Week> = serde_json::from_value(serde_json::json!([
+            { "contributionDays": [{ "contributionCount": 2 }, { "contributionCount": 3 }] },
+            { "contributionDays": [] }
+        ]))
+        .unwrap();
+        let totals = weekly_totals(&weeks);
+        let counts: Vec<Vec<i32>> = totals.iter().map(|w| w.contribution_days.iter().map(|d| d.contribution_count).collect()).collect();
+        assert_eq!(counts, vec![vec![5], vec![0]]);
+    }
+
+    #[test]
+    fn week_ticks_fall_every_n_weeks() {
+        let weeks: Vec<Week> = (0..53).map(|_| Week { contribution_days: Vec::new() }).collect();
+        let ticks = |n: usize| draw_week_ticks(&weeks, &Options { week_ticks: n, ..Options::default() }).to_string().matches("<path").count();
+        assert_eq!(ticks(0), 0);
+        assert_eq!(ticks(4), 13);
+        assert_eq!(ticks(60), 0);
+    }
+
+    #[test]
+    fn darken_keeps_hue_and_scales_lightness() {
+        assert_eq!(darken("#ff0000", 0.5), "#800000");
+        assert_eq!(darken("#c6e48b", 0.8), "#a9d64f");
+        assert_eq!(darken("#ebedf0", 0.6), "#7e8b9f");
+        assert_eq!(darken("#fff", 1.0), "#ffffff");
+    }
+
+    #[test]
+    fn darken_accepts_short_hex_and_rgba_and_passes_garbage_through() {
+        assert_eq!(darken("#abc", 1.0), darken("#aabbcc", 1.0));
+        assert_eq!(darken("#aabbcc", 1.0), "#aabbcc");
+        assert_eq!(darken("rgba(46,160,67,0.2)", 1.0), "rgba(46, 160, 67, 0.2)");
+        assert_eq!(darken("rgb(255, 0, 0)", 0.5), "#800000");
+        assert_eq!(darken("rgba(255, 0, 0, 0.5)", 0.5), "rgba(128, 0, 0, 0.5)");
+        for garbage in ["not a color", "rgba(1,2,3)", "rgb(300, 0, 0)", "#12345", ""] {
+            assert_eq!(darken(garbage, 0.5), garbage);
+        }
+    }
+
+    #[test]
+    fn monday_start_puts_monday_in_the_top_row() {
+        let day = |date: &str| Day { contribution_count: 1, date: Some(date.parse().unwrap()) };
+        // 2025-01-05 is a Sunday, 2025-01-06 the Monday after it
+        assert_eq!(cell_position(1, 0, &day("2025-01-05"), WeekStart::Sunday), (1, 0));
+        assert_eq!(cell_position(1, 1, &day("2025-01-06"), WeekStart::Sunday), (1, 1));
+        assert_eq!(cell_position(1, 1, &day("2025-01-06"), WeekStart::Monday), (2, 0));
+        assert_eq!(cell_position(1, 0, &day("2025-01-05"), WeekStart::Monday), (1, 6));
+        // Undated days can't be rotated
+        assert_eq!(cell_position(1, 3, &Day { contribution_count: 0, date: None }, WeekStart::Monday), (1, 3));
+    }
+
+    #[test]
+    fn mini_draws_only_flat_cells_in_a_square() {
+        let user = crate::demo::demo_user();
+        let svg = render_profile(&user, &Options { mini: true, ..Options::default() }).to_string();
+        let days = user.contributions_collection.contribution_calendar.weeks.iter().map(|w| w.contribution_days.len()).sum::<usize>();
+        assert_eq!(svg.matches("<polygon").count(), days);
+        assert!(!svg.contains("<text") && !svg.contains("<path"));
+        assert!(svg.contains("width=\"128\""));
+    }
+
+    #[test]
+    fn gradient_hits_its_stops_and_blends_between_them() {
+        let two = vec!["#000000".to_string(), "#ffffff".to_string()];
+        assert_eq!(gradient_color(&two, 0.0), "#000000");
+        assert_eq!(gradient_color(&two, 1.0), "#ffffff");
+        assert_eq!(gradient_color(&two, 0.5), "#808080");
+        assert_eq!(gradient_color(&two, 7.0), "#ffffff");
+
+        let three = vec!["#ff0000".to_string(), "#00ff00".to_string(), "#0000ff".to_string()];
+        assert_eq!(gradient_color(&three, 0.5), "#00ff00");
+        assert_eq!(gradient_color(&three, 0.75), "#008080");
+        assert_eq!(gradient_color(&three, 1.0), "#0000ff");
+    }
+
+    #[test]
+    fn long_ranges_shrink_the_projection_to_fit() {
+        let weeks = |n: usize| -> Vec<Week> { (0..n).map(|_| Week { contribution_days: Vec::new() }).collect() };
+        let opts = Options::default();
+        assert!(fitted_projection(&weeks(53), &opts).is_none());
+
+        // Twenty years of weeks
+        let proj = fitted_projection(&weeks(1043), &opts).expect("downscaled");
+        let (far_x, _) = proj.project(1043.0, 0.0, 0.0);
+        assert!(proj.scale < opts.projection.scale);
+        assert!((far_x - (VIEW_WIDTH - GRID_MARGIN)).abs() < 1e-6);
+    }
+
+    fn axis_labels_for(weeks: &[Week], axis_labels: AxisLabels) -> Vec<String> {
+        column_labels(weeks, &Options { axis_labels, ..Options::default() }).into_iter().map(|(_, label)| label).collect()
+    }
+
+    #[test]
+    fn weekday_labels_follow_the_week_start() {
+        assert_eq!(row_labels(&Options::default()), [(1, "Mon"), (3, "Wed"), (5, "Fri")]);
+        assert_eq!(row_labels(&Options { week_start: WeekStart::Monday, ..Options::default() }), [(0, "Mon"), (2, "Wed"), (4, "Fri")]);
+        assert!(row_labels(&Options { axis_labels: AxisLabels::None, ..Options::default() }).is_empty());
+    }
+
+    #[test]
+    fn axis_labels_mark_months_or_iso_weeks() {
+        let user = crate::demo::demo_user();
+        let weeks = &user.contributions_collection.contribution_calendar.weeks;
+        let labels = |axis_labels| axis_labels_for(weeks, axis_labels);
+        // The fixture opens with a one-column sliver of December 2024, which
+        // gives way to January
+        let months = labels(AxisLabels::Month);
+        assert_eq!(months.len(), 12);
+        assert_eq!((months[0].as_str(), months[11].as_str()), ("Jan", "Dec"));
+        // Its first column is ISO week 1 of 2025, carried by the Monday
+        let weeks_labels = labels(AxisLabels::Week);
+        assert_eq!(weeks_labels[0], "W1");
+        assert_eq!(weeks_labels[1], "W5");
+        assert!(labels(AxisLabels::None).is_empty());
+    }
+
+    // Every day from `from` to `to` with one contribution, in Sunday-first weeks
+    fn calendar(from: &str, to: &str) -> Vec<Week> {
+        let (from, to) = (Date::parse(from).unwrap(), Date::parse(to).unwrap());
+        let days = (0..=to.days_since_epoch() - from.days_since_epoch()).map(|i| (from.add_days(i), 1)).collect();
+        crate::merge::weeks_from_days(&days)
+    }
+
+    #[test]
+    fn leap_february_keeps_month_labels_and_separators_aligned() {
+        // Sunday 2023-12-31 onward: March 2024 begins on a Friday, after Feb 29
+        let weeks = calendar("2023-12-31", "2024-04-06");
+        let labels = column_labels(&weeks, &Options::default());
+        assert_eq!(labels, [(1, "Jan".to_string()), (5, "Feb".to_string()), (9, "Mar".to_string())]);
+        let iso = column_labels(&weeks, &Options { axis_labels: AxisLabels::Week, ..Options::default() });
+        assert_eq!(iso[2], (8, "W9".to_string()));
+
+        // 2024 has 366 days, putting 2025-01-01 on a Wednesday in column 52
+        let weeks = calendar("2023-12-31", "2025-01-04");
+        let cols = column_dates(&weeks, WeekStart::Sunday);
+        assert_eq!(cols[&52].iter().find(|(_, d)| *d == Date::parse("2025-01-01").unwrap()).map(|(row, _)| *row), Some(3));
+        let svg = draw_year_separators(&weeks, &Options::default()).to_string();
+        assert_eq!(svg.matches("<path").count(), 2);
+        assert!(svg.contains("2024") && svg.contains("2025"));
+    }
+
+    #[test]
+    fn gradient_background_is_defined_and_checked() {
+        let opts = Options { background: Background::Gradient(vec!["#ffffff".to_string(), "#5a6068".to_string()]), ..Options::default() };
+        let svg = render_profile(&crate::demo::demo_user(), &opts).to_string();
+        assert!(svg.contains("<linearGradient") && svg.contains("url(#hm-bg)"));
+        let warnings = background_warnings(&opts);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("#5a6068"));
+    }
+
+    #[test]
+    fn year_separators_mark_each_january_first() {
+        let user = crate::demo::demo_user();
+        let svg = draw_year_separators(&user.contributions_collection.contribution_calendar.weeks, &Options::default()).to_string();
+        // The fixture spans 2024-12-29 to 2025-12-31: one boundary, mid-column
+        assert_eq!(svg.matches("<path").count(), 1);
+        assert!(svg.contains("2025") && !svg.contains("2024"));
+        assert_eq!(svg.split(" d=\"").nth(1).unwrap().split('"').next().unwrap().matches('L').count(), 3);
+    }
+
+    #[test]
+    fn radar_benchmark_scales_linearly_against_references() {
+        let opts = Options { radar_benchmark: Some(DEFAULT_RADAR_BENCHMARK), ..Options::default() };
+        assert_eq!(radar_scale(500, 0, &opts), 0.5);
+        assert_eq!(radar_scale(40, 4, &opts), 2.0);
+        assert!((radar_scale(9999, 0, &Options::default()) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn canvas_limit_counts_cells_per_granularity() {
+        let user = crate::demo::demo_user();
+        let weeks = &user.contributions_collection.contribution_calendar.weeks;
+        assert!(check_canvas_size(weeks, &Options::default()).is_ok());
+        let err = check_canvas_size(weeks, &Options { max_canvas_bars: 100, ..Options::default() }).unwrap_err();
+        assert!(err.contains("--granularity week"));
+        assert!(check_canvas_size(weeks, &Options { max_canvas_bars: 100, granularity: Granularity::Week, ..Options::default() }).is_ok());
+    }
+
+    #[test]
+    fn empty_calendar_renders_a_placeholder() {
+        let user: User = serde_json::from_value(serde_json::json!({
+            "contributionsCollection": {
+                "totalCommitContributions": 0, "totalIssueContributions": 0,
+                "totalPullRequestContributions": 0, "totalPullRequestReviewContributions": 0,
+                "totalRepositoryContributions": 0,
+                "contributionCalendar": { "totalContributions": 0, "weeks": [] }
+            },
+            "repositories": { "nodes": [] }
+        }))
+        .unwrap();
+        for style in [Style::Bars, Style::Surface, Style::Flat] {
+            let svg = render_profile(&user, &Options { style, ..Options::default() }).to_string();
+            assert!(svg.contains(PLACEHOLDER_TEXT));
+        }
+    }
+
+    #[test]
+    fn projection_honours_custom_parameters() {
+        let proj = Projection { origin_x: 0.0, origin_y: 0.0, angle_deg: 45.0, scale: 10.0, mirrored: false };
+        let half = 10.0 * 45.0_f64.to_radians().cos();
+        assert_close(proj.project(1.0, 0.0, 5.0), (half, half - 5.0));
+    }
+
+    #[test]
+    fn repo_langs_panel_lists_most_starred_repositories() {
+        let user = crate::demo::demo_user();
+        let plain = render_profile(&user, &Options::default()).to_string();
+        assert!(!plain.contains("Repositories"));
+
+        let opts = Options { panels: crate::options::Panels { repo_langs: true, ..Default::default() }, ..Options::default() };
+        let svg = render_profile(&user, &opts).to_string();
+        let first = svg.find("heatmap-3d · Rust").unwrap();
+        let second = svg.find("dashboard-ui · TypeScript").unwrap();
+        assert!(first < second);
+        assert!(explain_layout(&user, &opts).contains("repo-langs: "));
+        assert_eq!(truncated("a-very-long-repository-name", 10), "a-very-lo…");
+    }
+}
@@ -0,0 +1,175 @@
+//! `--source rest`: an approximate profile built from the REST API, for
+//! tokens without GraphQL access or no token at all.
+//!
+//! GitHub only serves a user's last 300 public events (at most 90 days), so
+//! the calendar covers recent public activity only; private contributions
+//! and anything older are missing. Commits are counted per push, issues and
+//! pull requests when opened, and repositories when created.
+
+use crate::date::{Date, UtcOffset};
+use crate::github::{Api, ContributionCalendar, ContributionsCollection, DailyCounts, LangEdge, LangNode, Languages, RepoNode, Repositories, User};
+use crate::merge::weeks_from_days;
+use serde::Deserialize;
+use std::error::Error;
+
+// The events API stops after this many pages of 100
+const EVENT_PAGES: u32 = 3;
+
+#[derive(Deserialize, Debug)]
+pub struct Event {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub payload: serde_json::Value,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Repo {
+    #[serde(default)]
+    pub name: String,
+    pub stargazers_count: i32,
+    pub forks_count: i32,
+    pub language: Option<String>,
+    /// Repository size in KB, standing in for language bytes.
+    pub size: i32,
+}
+
+/// REST root for a GraphQL endpoint: `https://api.github.com` for the public
+/// API, `https://HOST/api/v3` for GitHub Enterprise's `https://HOST/api/graphql`.
+pub fn rest_base(graphql_url: &str) -> String {
+    let base = graphql_url.trim_end_matches('/').trim_end_matches("/graphql");
+    if base.ends_with("/api") { format!("{}/v3", base) } else { base.to_string() }
+}
+
+fn get<T: for<'de> Deserialize<'de>>(api: &Api, token: Option<&str>, path: &str) -> Result<T, Box<dyn Error>> {
+    let url = format!("{}{}", rest_base(&api.url), path);
+    let mut request = api.client.get(&url).header("User-Agent", "rust").header("Accept", "application/vnd.github+json");
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let res = api.send(request)?;
+    let status = res.status();
+    let text = res.text()?;
+    if !status.is_success() {
+        return Err(format!("GET {} failed with {}: {}", url, status, text.trim()).into());
+    }
+    Ok(serde_json::from_str(&text).map_err(|e| format!("unexpected response from {}: {}", url, e))?)
+}
+
+/// Fetches public events and owned repositories and folds them into a
+/// `User` whose calendar runs a year back from `today`.
+pub fn fetch_rest_user(api: &Api, token: Option<&str>, login: &str, today: Date, offset: UtcOffset) -> Result<User, Box<dyn Error>> {
+    let mut events: Vec<Event> = Vec::new();
+    for page in 1..=EVENT_PAGES {
+        let batch: Vec<Event> = get(api, token, &format!("/users/{}/events/public?per_page=100&page={}", login, page))?;
+        let done = batch.len() < 100;
+        events.extend(batch);
+        if done {
+            break;
+        }
+    }
+    let repos: Vec<Repo> = get(api, token, &format!("/users/{}/repos?type=owner&per_page=100", login))?;
+    Ok(user_from_events(login, &events, repos, today, offset))
+}
+
+// How many contributions an event stands for, and which total it adds to.
+fn weigh(event: &Event) -> Option<(Kind, i32)> {
+    let action = event.payload["action"].as_str();
+    match event.kind.as_str() {
+        "PushEvent" => Some((Kind::Commit, event.payload["size"].as_i64().unwrap_or(1).max(0) as i32)),
+        "IssuesEvent" if action == Some("opened") => Some((Kind::Issue, 1)),
+        "PullRequestEvent" if action == Some("opened") => Some((Kind::PullRequest, 1)),
+        "PullRequestReviewEvent" => Some((Kind::Review, 1)),
+        "CreateEvent" if event.payload["ref_type"] == "repository" => Some((Kind::Repository, 1)),
+        _ => None,
+    }
+}
+
+enum Kind {
+    Commit,
+    Issue,
+    PullRequest,
+    Review,
+    Repository,
+}
+
+/// The approximate profile for `events` and `repos`, dated at `offset`. The
+/// calendar starts on the Sunday a year before `today`, like GitHub's.
+pub fn user_from_events(login: &str, events: &[Event], repos: Vec<Repo>, today: Date, offset: UtcOffset) -> User {
+    let first = today.add_days(-364);
+    let first = first.add_days(-(first.weekday() as i64));
+    let mut days: DailyCounts = [(first, 0), (today, 0)].into_iter().collect();
+    let mut collection = ContributionsCollection {
+        total_commit_contributions: 0,
+        total_issue_contributions: 0,
+        total_pull_request_contributions: 0,
+        total_pull_request_review_contributions: 0,
+        total_repository_contributions: 0,
+        contribution_calendar: ContributionCalendar { total_contributions: 0, weeks: Vec::new() },
+    };
+    for event in events {
+        let (Some((kind, count)), Some(date)) = (weigh(event), offset.date_of(&event.created_at)) else { continue };
+        if date < first || date > today {
+            continue;
+        }
+        *days.entry(date).or_insert(0) += count;
+        collection.contribution_calendar.total_contributions += count;
+        *match kind {
+            Kind::Commit => &mut collection.total_commit_contributions,
+            Kind::Issue => &mut collection.total_issue_contributions,
+            Kind::PullRequest => &mut collection.total_pull_request_contributions,
+            Kind::Review => &mut collection.total_pull_request_review_contributions,
+            Kind::Repository => &mut collection.total_repository_contributions,
+        } += count;
+    }
+    collection.contribution_calendar.weeks = weeks_from_days(&days);
+
+    let nodes = repos
+        .into_iter()
+        .map(|repo| RepoNode {
+            name: repo.name,
+            stargazer_count: repo.stargazers_count,
+            fork_count: repo.forks_count,
+            languages: Some(Languages { edges: repo.language.map(|name| LangEdge { size: repo.size.max(1), node: LangNode { name, color: None } }).into_iter().collect() }),
+        })
+        .collect();
+    User { login: login.to_string(), name: None, avatar_url: None, created_at: None, contributions_collection: collection, repositories: Repositories { nodes, page_info: None } }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rest_base_follows_the_graphql_endpoint() {
+        assert_eq!(rest_base("https://api.github.com/graphql"), "https://api.github.com");
+        assert_eq!(rest_base("https://github.example.com/api/graphql"), "https://github.example.com/api/v3");
+    }
+
+    #[test]
+    fn events_bucket_into_a_year_long_calendar() {
+        let events: Vec<Event> = serde_json::from_value(serde_json::json!([
+            { "type": "PushEvent", "created_at": "2024-05-01T10:00:00Z", "payload": { "size": 3 } },
+            { "type": "PushEvent", "created_at": "2024-05-01T23:30:00Z", "payload": { "size": 2 } },
+            { "type": "PullRequestEvent", "created_at": "2024-05-02T09:00:00Z", "payload": { "action": "opened" } },
+            { "type": "PullRequestEvent", "created_at": "2024-05-02T10:00:00Z", "payload": { "action": "closed" } },
+            { "type": "WatchEvent", "created_at": "2024-05-02T11:00:00Z", "payload": {} },
+            { "type": "CreateEvent", "created_at": "2024-05-03T11:00:00Z", "payload": { "ref_type": "repository" } }
+        ]))
+        .unwrap();
+        let repos: Vec<Repo> = serde_json::from_value(serde_json::json!([{ "stargazers_count": 4, "forks_count": 1, "language": "Rust", "size": 120 }])).unwrap();
+        let today = Date::parse("2024-05-10").unwrap();
+        let user = user_from_events("octocat", &events, repos, today, "+01:00".parse().unwrap());
+
+        let c = &user.contributions_collection;
+        assert_eq!((c.total_commit_contributions, c.total_pull_request_contributions, c.total_repository_contributions), (5, 1, 1));
+        assert_eq!(c.contribution_calendar.total_contributions, 7);
+        let count = |s: &str| crate::github::iter_days(&c.contribution_calendar.weeks).find(|(_, d)| d.date == Date::parse(s)).map(|(_, d)| d.contribution_count);
+        // The late push lands on the next day at +01:00
+        assert_eq!((count("2024-05-01"), count("2024-05-02")), (Some(3), Some(3)));
+        assert_eq!(c.contribution_calendar.weeks[0].contribution_days[0].date.unwrap().weekday(), 0);
+        assert_eq!(c.contribution_calendar.weeks.last().unwrap().contribution_days.last().unwrap().date, Some(today));
+        assert_eq!(user.repositories.nodes[0].languages.as_ref().unwrap().edges[0].node.name, "Rust");
+    }
+}
@@ -0,0 +1,55 @@
+//! Experimental "year rings" layout: one concentric ring per month, innermost
+//! first, with a segment per day colored like the grid bars.
+
+use crate::github::{iter_days, Day, Week};
+use crate::options::Options;
+use crate::render::{annular_sector, cell_color, ColorScale};
+use std::f64::consts::PI;
+use svg::node::element::{Group, Path, Title};
+use svg::node::Text as TextNode;
+
+const CENTER: (f64, f64) = (560.0, 380.0);
+const INNER_RADIUS: f64 = 60.0;
+const OUTER_RADIUS: f64 = 340.0;
+const RING_WIDTH: f64 = 20.0;
+const RING_GAP: f64 = 3.0;
+
+// Groups days by calendar month; without dates, falls back to 4-week chunks.
+fn month_rings(weeks: &[Week]) -> Vec<Vec<(usize, &Day)>> {
+    let mut rings: Vec<Vec<(usize, &Day)>> = Vec::new();
+    let mut current = None;
+    for (i, (x, day)) in iter_days(weeks).enumerate() {
+        let key = day.date.map_or(i / 28, |d| (d.year * 12 + d.month as i32) as usize);
+        if current != Some(key) {
+            rings.push(Vec::new());
+            current = Some(key);
+        }
+        rings.last_mut().expect("pushed above").push((x, day));
+    }
+    rings
+}
+
+pub(crate) fn draw_year_rings(weeks: &[Week], opts: &Options) -> Group {
+    let mut g = Group::new().set("transform", format!("translate({}, {})", CENTER.0, CENTER.1));
+    let rings = month_rings(weeks);
+    let scale = ColorScale::new(weeks, opts);
+    // Thin the rings for long ranges so they stay inside the panel
+    let pitch = ((OUTER_RADIUS - INNER_RADIUS) / rings.len().max(1) as f64).min(RING_WIDTH + RING_GAP);
+    let (width, gap) = (pitch * RING_WIDTH / (RING_WIDTH + RING_GAP), pitch * RING_GAP / (RING_WIDTH + RING_GAP));
+    for (ring, days) in rings.iter().enumerate() {
+        let inner = INNER_RADIUS + ring as f64 * (width + gap);
+        let step = 2.0 * PI / days.len() as f64;
+        for (i, (x, day)) in days.iter().enumerate() {
+            // Start at 12 o'clock and run clockwise
+            let start = -PI / 2.0 + i as f64 * step;
+            let color = cell_color(*x, day.contribution_count, &scale, opts);
+            let mut segment = Path::new().set("d", annular_sector(inner + width, inner, start, start + step)).set("fill", color.as_str());
+            if let Some(date) = day.date {
+                let label = format!("{}: {}", opts.locale.format_date(date), opts.locale.format_int(day.contribution_count as i64));
+                segment = segment.add(Title::new().add(TextNode::new(label)));
+            }
+            g = g.add(segment);
+        }
+    }
+    g
+}
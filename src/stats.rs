@@ -0,0 +1,188 @@
+//! Summary numbers for a profile, as printed by the `stats` command.
+
+use crate::escape::escape_text;
+use crate::date::Date;
+use crate::github::{aggregate_languages, iter_days, language_breakdown, LanguageStat, User, Week};
+use serde::Serialize;
+use std::fmt;
+use svg::node::element::Element;
+use svg::node::{Node, Text};
+
+#[derive(Debug, Serialize)]
+pub struct ProfileStats {
+    pub total_contributions: i32,
+    pub commits: i32,
+    pub issues: i32,
+    pub pull_requests: i32,
+    pub reviews: i32,
+    pub repositories_created: i32,
+    pub stars: i32,
+    pub forks: i32,
+    pub streaks: Streaks,
+    pub languages: Vec<LanguageStat>,
+}
+
+/// Statistics as of `today`, which callers pass in (usually
+/// `UtcOffset::today`) so the result doesn't depend on when it runs.
+pub fn profile_stats(user: &User, today: Date) -> ProfileStats {
+    let c = &user.contributions_collection;
+    let repos = &user.repositories.nodes;
+    ProfileStats {
+        total_contributions: c.contribution_calendar.total_contributions,
+        commits: c.total_commit_contributions,
+        issues: c.total_issue_contributions,
+        pull_requests: c.total_pull_request_contributions,
+        reviews: c.total_pull_request_review_contributions,
+        repositories_created: c.total_repository_contributions,
+        stars: repos.iter().map(|r| r.stargazer_count).sum(),
+        forks: repos.iter().map(|r| r.fork_count).sum(),
+        streaks: streaks(&c.contribution_calendar.weeks, today),
+        languages: language_breakdown(aggregate_languages(repos)),
+    }
+}
+
+/// Runs of consecutive days with at least one contribution.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Streaks {
+    pub longest: usize,
+    /// The run ending `today`, or yesterday while today has no contributions
+    /// yet; zero once a full day has been missed.
+    pub current: usize,
+}
+
+/// Streaks over the dated days up to `today`; later days are ignored.
+pub fn streaks(weeks: &[Week], today: Date) -> Streaks {
+    let active: Vec<Date> = iter_days(weeks).filter_map(|(_, d)| d.date.filter(|&date| date <= today && d.contribution_count > 0)).collect();
+    let (mut longest, mut run, mut last) = (0, 0, None::<Date>);
+    for &date in &active {
+        run = if last.is_some_and(|l| l.add_days(1) == date) { run + 1 } else { 1 };
+        longest = longest.max(run);
+        last = Some(date);
+    }
+    let current = match last {
+        Some(l) if l == today || l.add_days(1) == today => run,
+        _ => 0,
+    };
+    Streaks { longest, current }
+}
+
+/// The `n` busiest dated days, most contributions first and earlier dates
+/// first among ties. Days without contributions never make the list.
+pub fn top_days(weeks: &[Week], n: usize) -> Vec<(Date, i32)> {
+    let mut days: Vec<(Date, i32)> = iter_days(weeks).filter_map(|(_, d)| Some((d.date?, d.contribution_count))).filter(|&(_, c)| c > 0).collect();
+    days.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    days.truncate(n);
+    days
+}
+
+/// `--embed-data`: the daily series as compact `[date, count]` pairs plus
+/// the `stats` numbers, as JSON in a `<metadata>` element so the card can be
+/// read back without querying GitHub again. Streaks are as of the
+/// calendar's last day, keeping the card a pure function of its data; a
+/// calendar without dates has no last day, so `stats` is left out.
+pub(crate) fn data_island(user: &User) -> Element {
+    let last = iter_days(&user.contributions_collection.contribution_calendar.weeks).filter_map(|(_, d)| d.date).max();
+    let days: Vec<(String, i32)> = iter_days(&user.contributions_collection.contribution_calendar.weeks)
+        .filter_map(|(_, d)| Some((d.date?.to_string(), d.contribution_count)))
+        .collect();
+    let mut json = serde_json::json!({ "days": days });
+    if let Some(last) = last {
+        json["stats"] = serde_json::json!(profile_stats(user, last));
+    }
+    let mut metadata = Element::new("metadata");
+    metadata.assign("id", "heatmap-data");
+    metadata.append(Text::new(escape_text(&json.to_string())));
+    metadata
+}
+
+/// What a fetch actually returned, printed under `--verbose` to tell a thin API
+/// response apart from a rendering problem.
+#[derive(Debug, PartialEq)]
+pub struct FetchSummary {
+    pub weeks: usize,
+    pub days: usize,
+    pub nonzero_days: usize,
+    pub max_count: i32,
+    pub repositories: usize,
+    pub languages: usize,
+    pub language_bytes: i64,
+}
+
+pub fn summarize(user: &User) -> FetchSummary {
+    let weeks = &user.contributions_collection.contribution_calendar.weeks;
+    let days = || weeks.iter().flat_map(|w| &w.contribution_days);
+    let langs = aggregate_languages(&user.repositories.nodes);
+    FetchSummary {
+        weeks: weeks.len(),
+        days: days().count(),
+        nonzero_days: days().filter(|d| d.contribution_count > 0).count(),
+        max_count: days().map(|d| d.contribution_count).max().unwrap_or(0),
+        repositories: user.repositories.nodes.len(),
+        languages: langs.len(),
+        language_bytes: langs.values().map(|(size, _)| *size).sum(),
+    }
+}
+
+impl fmt::Display for FetchSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "fetched {} weeks, {} days ({} nonzero, max {})", self.weeks, self.days, self.nonzero_days, self.max_count)?;
+        write!(f, "fetched {} repositories, {} languages, {} bytes", self.repositories, self.languages, self.language_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::demo::demo_user;
+    use crate::github::Day;
+
+    #[test]
+    fn summary_counts_the_demo_profile() {
+        let summary = summarize(&demo_user());
+        assert_eq!(summary.weeks, 53);
+        assert_eq!(summary.max_count, 41);
+        assert_eq!(summary.repositories, 6);
+        assert!(summary.nonzero_days <= summary.days);
+        assert!(summary.to_string().contains("53 weeks"));
+    }
+
+    #[test]
+    fn top_days_rank_by_count_then_date() {
+        let weeks = &demo_user().contributions_collection.contribution_calendar.weeks;
+        let top = top_days(weeks, 3);
+        assert_eq!(top.len(), 3);
+        assert_eq!(top[0].1, 41);
+        assert!(top.windows(2).all(|w| w[0].1 > w[1].1 || (w[0].1 == w[1].1 && w[0].0 < w[1].0)));
+        assert!(top_days(weeks, 0).is_empty());
+    }
+
+    #[test]
+    fn current_streak_is_ongoing_until_a_day_is_missed() {
+        let start = Date::parse("2024-03-01").unwrap();
+        let counts = [2, 0, 1, 1, 3, 0, 4, 1, 2, 5];
+        let weeks = vec![Week { contribution_days: counts.iter().enumerate().map(|(i, &c)| Day { contribution_count: c, date: Some(start.add_days(i as i64)) }).collect() }];
+
+        let last = start.add_days(9);
+        assert_eq!(streaks(&weeks, last), Streaks { longest: 4, current: 4 });
+        // Nothing yet today still counts yesterday's run
+        assert_eq!(streaks(&weeks, last.add_days(1)).current, 4);
+        assert_eq!(streaks(&weeks, last.add_days(2)).current, 0);
+        // Days after "today" are ignored
+        assert_eq!(streaks(&weeks, start.add_days(4)), Streaks { longest: 3, current: 3 });
+    }
+
+    #[test]
+    fn data_island_round_trips_the_series() {
+        let user = demo_user();
+        let svg = data_island(&user).to_string();
+        let json = svg.split_once('>').unwrap().1.rsplit_once("</metadata>").unwrap().0;
+        let json: serde_json::Value = serde_json::from_str(&json.replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&")).unwrap();
+        assert_eq!(json["stats"]["total_contributions"], user.contributions_collection.contribution_calendar.total_contributions);
+        assert_eq!(json["days"][0][0], user.contributions_collection.contribution_calendar.weeks[0].contribution_days[0].date.unwrap().to_string());
+
+        let mut undated = demo_user();
+        undated.contributions_collection.contribution_calendar.weeks.iter_mut().flat_map(|w| &mut w.contribution_days).for_each(|d| d.date = None);
+        let svg = data_island(&undated).to_string();
+        assert!(svg.contains("days") && !svg.contains("stats"), "{}", svg);
+    }
+}
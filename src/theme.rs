@@ -0,0 +1,160 @@
+//! Card colors. Drawing code reads these instead of hardcoding hex values.
+//!
+//! Besides the built-in `light` and `dark` presets (`--theme`), a theme can be
+//! loaded from a JSON file with one `#RRGGBB` (or `#RGB`) string per field;
+//! see `themes/example.json`. `grid`, `text` and `empty_cell` may be left out
+//! and keep the light theme's colors.
+
+use serde::Deserialize;
+use std::path::Path;
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Theme {
+    pub background: String,
+    /// Highlight color for data overlays such as the radar polygon. Mid-tone
+    /// so it reads on both light and dark backgrounds.
+    pub accent: String,
+    /// Faint guide lines: radar rings and week ticks.
+    #[serde(default = "default_grid")]
+    pub grid: String,
+    /// Labels, titles and the footer.
+    #[serde(default = "default_text")]
+    pub text: String,
+    /// Fill for days without contributions in the seasonal and intensity
+    /// palettes, and for the empty-calendar placeholder.
+    #[serde(default = "default_empty_cell")]
+    pub empty_cell: String,
+}
+
+fn default_grid() -> String {
+    Theme::light().grid
+}
+
+fn default_text() -> String {
+    Theme::light().text
+}
+
+fn default_empty_cell() -> String {
+    Theme::light().empty_cell
+}
+
+impl Theme {
+    pub fn light() -> Self {
+        Theme {
+            background: "#ffffff".to_string(),
+            accent: "#2ea043".to_string(),
+            grid: "#e1e4e8".to_string(),
+            text: "#586069".to_string(),
+            empty_cell: "#ebedf0".to_string(),
+        }
+    }
+
+    /// GitHub's dark palette: light gray text and empty cells a step above
+    /// the background so the grid stays visible.
+    pub fn dark() -> Self {
+        Theme {
+            background: "#0d1117".to_string(),
+            accent: "#2ea043".to_string(),
+            grid: "#30363d".to_string(),
+            text: "#8b949e".to_string(),
+            empty_cell: "#161b22".to_string(),
+        }
+    }
+
+    /// Parses and validates a theme from JSON. `background` and `accent` are
+    /// required; `grid`, `text` and `empty_cell` default to the light theme's.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let theme: Theme = serde_json::from_str(json).map_err(|e| format!("invalid theme: {}", e))?;
+        theme.validate()?;
+        Ok(theme)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path).map_err(|e| format!("reading theme {}: {}", path.display(), e))?;
+        Theme::from_json(&json).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for (field, value) in [("background", &self.background), ("accent", &self.accent), ("grid", &self.grid), ("text", &self.text), ("empty_cell", &self.empty_cell)] {
+            if !is_hex_color(value) {
+                return Err(format!("theme field `{}` is not a hex color: {:?}", field, value));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Theme {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "light" => Ok(Theme::light()),
+            "dark" => Ok(Theme::dark()),
+            other => Err(format!("unknown theme: {} (expected light|dark, or --theme-file for a custom one)", other)),
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::light()
+    }
+}
+
+/// WCAG 2 contrast ratio between two hex colors (1.0 to 21.0), or `None` if
+/// either doesn't parse.
+pub fn contrast_ratio(a: &str, b: &str) -> Option<f64> {
+    let luminance = |hex: &str| -> Option<f64> {
+        let hex = hex.strip_prefix('#')?;
+        let hex = if hex.len() == 3 { hex.chars().flat_map(|c| [c, c]).collect() } else { hex.to_string() };
+        let channel = |i: usize| -> Option<f64> {
+            let c = u8::from_str_radix(hex.get(i..i + 2)?, 16).ok()? as f64 / 255.0;
+            Some(if c <= 0.03928 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) })
+        };
+        (hex.len() == 6).then_some(())?;
+        Some(0.2126 * channel(0)? + 0.7152 * channel(2)? + 0.0722 * channel(4)?)
+    };
+    let (la, lb) = (luminance(a)?, luminance(b)?);
+    Some((la.max(lb) + 0.05) / (la.min(lb) + 0.05))
+}
+
+pub fn is_hex_color(s: &str) -> bool {
+    s.strip_prefix('#').is_some_and(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_theme_file_deserializes() {
+        let theme = Theme::from_json(include_str!("../themes/example.json")).unwrap();
+        assert_eq!(theme.background, "#0d1117");
+        assert_eq!(theme.text, "#8b949e");
+    }
+
+    #[test]
+    fn dark_theme_keeps_labels_and_empty_cells_visible() {
+        let dark: Theme = "dark".parse().unwrap();
+        assert!(contrast_ratio(&dark.text, &dark.background).unwrap() >= 4.5);
+        assert!(contrast_ratio(&dark.empty_cell, &dark.background).unwrap() > 1.0);
+        // Older theme files without the newer fields still load
+        let theme = Theme::from_json(r##"{"background": "#000", "accent": "#2ea043", "grid": "#333"}"##).unwrap();
+        assert_eq!(theme.empty_cell, Theme::light().empty_cell);
+    }
+
+    #[test]
+    fn contrast_ratio_spans_one_to_twenty_one() {
+        assert!((contrast_ratio("#000000", "#ffffff").unwrap() - 21.0).abs() < 1e-9);
+        assert!((contrast_ratio("#777", "#777777").unwrap() - 1.0).abs() < 1e-9);
+        assert!(contrast_ratio("#fff", "white").is_none());
+    }
+
+    #[test]
+    fn missing_fields_and_bad_hex_are_rejected() {
+        assert!(Theme::from_json(r##"{"background": "#fff"}"##).unwrap_err().contains("accent"));
+        assert!(Theme::from_json(r##"{"background": "white", "accent": "#2ea043"}"##).unwrap_err().contains("background"));
+        assert_eq!(Theme::from_json(r##"{"background": "#000", "accent": "#2ea043"}"##).unwrap().grid, Theme::light().grid);
+    }
+}
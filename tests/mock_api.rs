@@ -0,0 +1,168 @@
+//! End-to-end fetch against a local stand-in for the GraphQL endpoint: the
+//! binary is pointed at it with `--api-url`, so request construction, response
+//! parsing and rendering all run without real credentials.
+
+use heatmap_rust::demo::demo_user;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+
+// Answers one request per body in order, handing back each request's line
+// (e.g. `GET /users/octocat/repos HTTP/1.1`) and body.
+fn serve(listener: TcpListener, bodies: Vec<String>) -> thread::JoinHandle<Vec<(String, String)>> {
+    thread::spawn(move || {
+        let mut requests = Vec::new();
+        for body in bodies {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':')
+                    && name.eq_ignore_ascii_case("content-length")
+                {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut request = vec![0; content_length];
+            reader.read_exact(&mut request).unwrap();
+
+            let mut stream = reader.into_inner();
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body).unwrap();
+            requests.push((request_line.trim().to_string(), String::from_utf8(request).unwrap()));
+        }
+        requests
+    })
+}
+
+fn run_render(dir: &Path, args: &[&str]) -> std::process::Output {
+    fs::create_dir_all(dir).unwrap();
+    Command::new(env!("CARGO_BIN_EXE_heatmap-rust"))
+        .args(args)
+        .current_dir(dir)
+        .env("NO_PROXY", "127.0.0.1")
+        .env_remove("HTTP_PROXY")
+        .env_remove("http_proxy")
+        .env_remove("GITHUB_TOKEN")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn render_fetches_from_the_configured_endpoint() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/graphql", listener.local_addr().unwrap());
+    let response = serde_json::json!({ "data": { "user": demo_user() } }).to_string();
+    let server = serve(listener, vec![response]);
+
+    let dir = std::env::temp_dir().join(format!("heatmap-mock-api-{}", std::process::id()));
+    let output = run_render(&dir, &["render", "--user", "octocat", "--token", "test-token", "--api-url", &url]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    let request: serde_json::Value = serde_json::from_str(&server.join().unwrap()[0].1).unwrap();
+    assert_eq!(request["variables"]["login"], "octocat");
+    assert!(request["query"].as_str().unwrap().contains("contributionCalendar"));
+
+    let svg = fs::read_to_string(dir.join("github_extended_no_overlap.svg")).unwrap();
+    fs::remove_dir_all(&dir).ok();
+    assert!(svg.starts_with("<svg"));
+    let user = demo_user();
+    let top_language = &user.repositories.nodes[0].languages.as_ref().unwrap().edges[0].node.name;
+    assert!(svg.contains(top_language.as_str()));
+}
+
+#[test]
+fn rest_source_renders_without_a_token() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/api/graphql", listener.local_addr().unwrap());
+    let events = serde_json::json!([{ "type": "PushEvent", "created_at": "2020-01-01T00:00:00Z", "payload": { "size": 2 } }]).to_string();
+    let repos = serde_json::json!([{ "stargazers_count": 7, "forks_count": 0, "language": "Zig", "size": 10 }]).to_string();
+    let server = serve(listener, vec![events, repos]);
+
+    let dir = std::env::temp_dir().join(format!("heatmap-mock-rest-{}", std::process::id()));
+    let output = run_render(&dir, &["render", "--user", "octocat", "--source", "rest", "--api-url", &url]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("approximates"));
+
+    let requests = server.join().unwrap();
+    assert!(requests[0].0.starts_with("GET /api/v3/users/octocat/events/public?"));
+    assert!(requests[1].0.starts_with("GET /api/v3/users/octocat/repos?"));
+    let svg = fs::read_to_string(dir.join("github_extended_no_overlap.svg")).unwrap();
+    fs::remove_dir_all(&dir).ok();
+    assert!(svg.contains("Zig") && svg.contains("Approximate"));
+}
+
+#[test]
+fn repositories_are_fetched_page_by_page() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/graphql", listener.local_addr().unwrap());
+    let mut first = serde_json::json!({ "data": { "user": demo_user() } });
+    first["data"]["user"]["repositories"]["pageInfo"] = serde_json::json!({ "hasNextPage": true, "endCursor": "c1" });
+    let zig = serde_json::json!({ "name": "ziggy", "stargazerCount": 1, "forkCount": 0, "languages": { "edges": [{ "size": 1_000_000_000, "node": { "name": "Zig", "color": "#ec915c" } }] } });
+    // The last page repeats its cursor; the fetch must stop rather than loop
+    let second = serde_json::json!({ "data": { "user": { "repositories": { "nodes": [zig], "pageInfo": { "hasNextPage": true, "endCursor": "c1" } } } } });
+    let server = serve(listener, vec![first.to_string(), second.to_string()]);
+
+    let dir = std::env::temp_dir().join(format!("heatmap-mock-pages-{}", std::process::id()));
+    let output = run_render(&dir, &["render", "--user", "octocat", "--token", "test-token", "--api-url", &url]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+
+    let requests = server.join().unwrap();
+    let page: serde_json::Value = serde_json::from_str(&requests[1].1).unwrap();
+    assert_eq!(page["variables"]["after"], "c1");
+    let svg = fs::read_to_string(dir.join("github_extended_no_overlap.svg")).unwrap();
+    fs::remove_dir_all(&dir).ok();
+    assert!(svg.contains("Zig"));
+}
+
+#[test]
+fn merged_card_is_written_as_streamed() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/graphql", listener.local_addr().unwrap());
+    let response = serde_json::json!({ "data": { "user": demo_user() } }).to_string();
+    let server = serve(listener, vec![response.clone(), response]);
+
+    let dir = std::env::temp_dir().join(format!("heatmap-mock-merge-{}", std::process::id()));
+    let output = run_render(&dir, &["render", "--merge", "octocat,hubot", "--token", "test-token", "--api-url", &url, "--output", "merged.svg"]);
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(server.join().unwrap().len(), 2);
+
+    let svg = fs::read_to_string(dir.join("merged.svg")).unwrap();
+    fs::remove_dir_all(&dir).ok();
+    let merged = heatmap_rust::merge::merge_users(vec![demo_user(), demo_user()]);
+    assert_eq!(svg, heatmap_rust::render_profile(&merged, &heatmap_rust::Options::default()).to_string());
+}
+
+#[test]
+fn users_file_cards_go_through_the_single_card_path() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/graphql", listener.local_addr().unwrap());
+    let found = serde_json::json!({ "data": { "user": demo_user() } }).to_string();
+    let missing = serde_json::json!({ "data": { "user": null }, "errors": [{ "type": "NOT_FOUND", "message": "Could not resolve to a User" }] }).to_string();
+    let server = serve(listener, vec![found, missing]);
+
+    let dir = std::env::temp_dir().join(format!("heatmap-mock-batch-{}", std::process::id()));
+    fs::create_dir_all(dir.join("cards")).unwrap();
+    fs::write(dir.join("users.txt"), "octocat\nghost # left the org\n").unwrap();
+    let output = run_render(&dir, &["render", "--users-file", "users.txt", "--out-dir", "cards", "--token", "test-token", "--api-url", &url, "--annotate", "1999-01-01:Launch"]);
+    server.join().unwrap();
+    let (stdout, stderr) = (String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+    let card = fs::read_to_string(dir.join("cards").join("octocat.svg"));
+    fs::remove_dir_all(&dir).ok();
+
+    assert!(!output.status.success());
+    assert!(stdout.contains("1 of 2 cards generated"), "stdout: {}", stdout);
+    assert!(stderr.contains("error: ghost"), "stderr: {}", stderr);
+    // Warned about by write_card, once for the card it wrote
+    assert_eq!(stderr.matches("skipping --annotate 1999-01-01").count(), 1, "stderr: {}", stderr);
+    assert!(card.unwrap().starts_with("<svg"));
+}
@@ -0,0 +1,49 @@
+//! Renders the demo profile under every theme × style × layout × color-mode
+//! combination and checks each card is well-formed, so a combination that
+//! breaks (a panic, NaN coordinates) is reported by name.
+
+use heatmap_rust::demo::demo_user;
+use heatmap_rust::options::ColorMode;
+use heatmap_rust::theme::Theme;
+use heatmap_rust::{render_profile, HeatmapLayout, Options, Style};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+fn themes() -> Vec<(&'static str, Theme)> {
+    vec![("light", Theme::light()), ("example", Theme::from_json(include_str!("../themes/example.json")).unwrap())]
+}
+
+// Any token that parses as a non-finite float (`NaN`, `inf`, `-inf`) in the
+// attribute soup of the document.
+fn has_non_finite(svg: &str) -> bool {
+    svg.split(|c: char| c.is_whitespace() || matches!(c, ',' | '"' | '(' | ')' | '>' | '<')).any(|t| t.parse::<f64>().is_ok_and(|v| !v.is_finite()))
+}
+
+#[test]
+fn every_combination_renders_clean_svg() {
+    let user = demo_user();
+    let mut failures = Vec::new();
+    for (theme_name, theme) in themes() {
+        for style in [Style::Bars, Style::Surface, Style::Flat, Style::Sparkline] {
+            for layout in [HeatmapLayout::Grid, HeatmapLayout::Rings] {
+                for color_mode in [ColorMode::Seasonal, ColorMode::Gradient, ColorMode::Intensity] {
+                    let combo = format!("theme={} style={:?} layout={:?} color-mode={:?}", theme_name, style, layout, color_mode);
+                    let opts = Options { theme: theme.clone(), style, layout, color_mode, ..Options::default() };
+                    match catch_unwind(AssertUnwindSafe(|| render_profile(&user, &opts).to_string())) {
+                        Err(_) => failures.push(format!("{}: panicked", combo)),
+                        Ok(svg) if has_non_finite(&svg) => failures.push(format!("{}: non-finite coordinate", combo)),
+                        Ok(svg) if !(svg.starts_with("<svg") && svg.ends_with("</svg>")) => failures.push(format!("{}: malformed document", combo)),
+                        Ok(_) => {}
+                    }
+                }
+            }
+        }
+    }
+    assert!(failures.is_empty(), "broken combinations:\n{}", failures.join("\n"));
+}
+
+#[test]
+fn non_finite_detection() {
+    assert!(has_non_finite(r#"<polygon points="1,NaN 2,3"/>"#));
+    assert!(has_non_finite(r#"<text x="-inf">"#));
+    assert!(!has_non_finite(r#"<text x="4">Informix</text>"#));
+}